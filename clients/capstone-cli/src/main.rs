@@ -0,0 +1,219 @@
+//! Command-line tool to create, attest, settle and inspect meditation plans against any RPC
+//! node, including a local test validator.
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use capstone_client::instructions::{self, PlanParams};
+use capstone_client::pda;
+use clap::{Args, Parser, Subcommand};
+use solana_signer::Signer;
+
+mod rpc;
+mod summary;
+#[cfg(test)]
+mod tests;
+
+use rpc::Rpc;
+use summary::PlanSummary;
+
+#[derive(Parser)]
+#[command(name = "capstone-cli", version, about)]
+struct Cli {
+    /// RPC URL of the cluster, e.g. http://127.0.0.1:8899 for a local test validator
+    #[arg(
+        long,
+        short = 'u',
+        env = "CAPSTONE_RPC_URL",
+        default_value = "http://127.0.0.1:8899",
+        global = true
+    )]
+    url: String,
+
+    /// Keypair signing and paying for transactions
+    #[arg(long, short = 'k', env = "CAPSTONE_KEYPAIR", global = true)]
+    keypair: Option<PathBuf>,
+
+    /// Mint of the stake token - defaults to the USDC mint the program was built for
+    #[arg(long, env = "CAPSTONE_MINT", default_value_t = capstone::USDC_MINT, global = true)]
+    mint: Pubkey,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a plan for the keypair and stake from its token account
+    CreatePlan(CreatePlanArgs),
+    /// Attest a session - defaults to one of the plan's duration ending now
+    Attest {
+        plan: Pubkey,
+        /// Unix timestamp the session started at
+        #[arg(long)]
+        started_at: Option<i64>,
+        /// Unix timestamp the session ended at
+        #[arg(long)]
+        ended_at: Option<i64>,
+    },
+    /// Settle a plan - as its owner, or as a keeper once the owner's grace period is over
+    Complete { plan: Pubkey },
+    /// Show the plan's status, sessions and projected payout
+    Status { plan: Pubkey },
+    /// Print the address of an owner's plan
+    PlanAddress { owner: Pubkey, id: u64 },
+    /// Upgrade a plan still in the legacy layout - anyone can pay for it
+    MigratePlan { plan: Pubkey },
+    /// Cancel a plan that hasn't started and refund its stake
+    CancelPlan { plan: Pubkey },
+    /// Close a completed or cancelled plan and reclaim its rent
+    ClosePlan { plan: Pubkey },
+}
+
+#[derive(Args)]
+struct CreatePlanArgs {
+    /// Identifies the plan among the owner's plans
+    #[arg(long)]
+    id: u64,
+    #[arg(long)]
+    days: u8,
+    /// Sessions per day
+    #[arg(long, default_value_t = 1)]
+    frequency: u8,
+    /// Minimum length of a session in minutes
+    #[arg(long)]
+    duration: u8,
+    /// Stake in base units of the mint (1 USDC = 1000000)
+    #[arg(long)]
+    stake: u64,
+    /// Wallet receiving the payouts instead of the owner
+    #[arg(long)]
+    beneficiary: Option<Pubkey>,
+    /// Registry account of the charity receiving the penalties
+    #[arg(long)]
+    charity: Option<Pubkey>,
+    /// Unix timestamp to start the plan at instead of now - up to 30 days ahead
+    #[arg(long)]
+    start_at: Option<i64>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rpc = Rpc::new(&cli.url, cli.keypair.as_deref())?;
+
+    match cli.command {
+        Command::CreatePlan(args) => {
+            let payer = rpc.keypair()?.pubkey();
+            let (meditation_plan, _bump) = pda::meditation_plan(&payer, args.id);
+            let instruction = instructions::initialize(
+                payer,
+                pda::token_account(&payer, &cli.mint),
+                cli.mint,
+                args.id,
+                PlanParams {
+                    number_of_days: args.days,
+                    daily_frequency: args.frequency,
+                    duration_minutes: args.duration,
+                    commitment_stake: args.stake,
+                },
+                args.beneficiary,
+                args.charity,
+                args.start_at,
+            );
+            rpc.send(instruction)?;
+            println!("Created plan {meditation_plan}");
+        }
+        Command::Attest {
+            plan,
+            started_at,
+            ended_at,
+        } => {
+            let payer = rpc.keypair()?.pubkey();
+            let meditation_plan = rpc.meditation_plan(&plan)?;
+            let ended_at = match ended_at {
+                Some(ended_at) => ended_at,
+                None => rpc.now()?,
+            };
+            let started_at =
+                started_at.unwrap_or(ended_at - meditation_plan.duration_minutes as i64 * 60);
+            let instruction =
+                instructions::attest(payer, meditation_plan.owner, plan, started_at, ended_at);
+            rpc.send(instruction)?;
+            println!("Attested session {started_at}..{ended_at} on plan {plan}");
+        }
+        Command::Complete { plan } => {
+            let payer = rpc.keypair()?.pubkey();
+            let meditation_plan = rpc.meditation_plan(&plan)?;
+            let charity_wallet = match meditation_plan.has_charity() {
+                true => Some(rpc.charity(&meditation_plan.charity)?.wallet),
+                false => None,
+            };
+            let mut instruction = if meditation_plan.owner == payer {
+                instructions::complete(
+                    payer,
+                    cli.mint,
+                    plan,
+                    meditation_plan.beneficiary,
+                    pda::token_account(&meditation_plan.beneficiary, &cli.mint),
+                    charity_wallet,
+                )
+            } else {
+                instructions::settle_expired(
+                    payer,
+                    meditation_plan.owner,
+                    cli.mint,
+                    plan,
+                    meditation_plan.beneficiary,
+                    charity_wallet,
+                )
+            };
+            if meditation_plan.is_lending() {
+                instruction.accounts.extend(instructions::lending_accounts(
+                    meditation_plan.lending_program,
+                    cli.mint,
+                    plan,
+                ));
+            }
+            rpc.send(instruction)?;
+            println!("Settled plan {plan}");
+            if meditation_plan.owner == payer && meditation_plan.all_sessions_completed() {
+                rpc.send(instructions::mint_badge(payer, plan))?;
+                println!("Minted completion badge {}", pda::badge_mint(&plan).0);
+            }
+        }
+        Command::Status { plan } => {
+            let meditation_plan = rpc.meditation_plan(&plan)?;
+            println!("{}", PlanSummary::new(plan, &meditation_plan, rpc.now()?)?);
+        }
+        Command::PlanAddress { owner, id } => {
+            println!("{}", pda::meditation_plan(&owner, id).0);
+        }
+        Command::MigratePlan { plan } => {
+            let payer = rpc.keypair()?.pubkey();
+            rpc.send(instructions::migrate_plan(payer, plan))?;
+            println!("Migrated plan {plan}");
+        }
+        Command::CancelPlan { plan } => {
+            let payer = rpc.keypair()?.pubkey();
+            let meditation_plan = rpc.meditation_plan(&plan)?;
+            let mut instruction = instructions::cancel_plan(payer, cli.mint, plan);
+            if meditation_plan.is_lending() {
+                instruction.accounts.extend(instructions::lending_accounts(
+                    meditation_plan.lending_program,
+                    cli.mint,
+                    plan,
+                ));
+            }
+            rpc.send(instruction)?;
+            println!("Cancelled plan {plan}");
+        }
+        Command::ClosePlan { plan } => {
+            let payer = rpc.keypair()?.pubkey();
+            rpc.send(instructions::close_plan(payer, cli.mint, plan))?;
+            println!("Closed plan {plan}");
+        }
+    }
+
+    Ok(())
+}
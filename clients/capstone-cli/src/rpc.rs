@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anyhow::{anyhow, Context, Result};
+use capstone_client::errors::instruction_error;
+use capstone_client::{accounts, Charity, MeditationPlan};
+use solana_commitment_config::CommitmentConfig;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_rpc_client::rpc_client::RpcClient;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+/// RPC connection and the keypair signing for the current command. The keypair is only loaded
+/// by commands that send transactions, so inspecting plans doesn't need one.
+pub struct Rpc {
+    client: RpcClient,
+    keypair_path: PathBuf,
+}
+
+impl Rpc {
+    pub fn new(url: &str, keypair_path: Option<&Path>) -> Result<Self> {
+        let keypair_path = match keypair_path {
+            Some(path) => path.to_path_buf(),
+            None => default_keypair_path()?,
+        };
+        Ok(Self {
+            client: RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed()),
+            keypair_path,
+        })
+    }
+
+    pub fn keypair(&self) -> Result<Keypair> {
+        read_keypair_file(&self.keypair_path).map_err(|error| {
+            anyhow!(
+                "Can't read keypair {}: {error}",
+                self.keypair_path.display()
+            )
+        })
+    }
+
+    /// Cluster time of the latest confirmed block, which is what the program compares plans against
+    pub fn now(&self) -> Result<i64> {
+        let slot = self.client.get_slot()?;
+        Ok(self.client.get_block_time(slot)?)
+    }
+
+    pub fn meditation_plan(&self, address: &Pubkey) -> Result<MeditationPlan> {
+        let data = self
+            .client
+            .get_account_data(address)
+            .with_context(|| format!("Can't fetch plan {address}"))?;
+        accounts::meditation_plan(&data).map_err(|error| anyhow!("{address} isn't a plan: {error}"))
+    }
+
+    pub fn charity(&self, address: &Pubkey) -> Result<Charity> {
+        let data = self
+            .client
+            .get_account_data(address)
+            .with_context(|| format!("Can't fetch charity {address}"))?;
+        accounts::charity(&data).map_err(|error| anyhow!("{address} isn't a charity: {error}"))
+    }
+
+    /// Signs the instruction with the keypair, which also pays the fees, and waits for confirmation.
+    /// Program errors are reported by name.
+    pub fn send(&self, instruction: Instruction) -> Result<()> {
+        let keypair = self.keypair()?;
+        let blockhash = self.client.get_latest_blockhash()?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&keypair.pubkey()),
+            &[&keypair],
+            blockhash,
+        );
+        let signature = self
+            .client
+            .send_and_confirm_transaction(&transaction)
+            .map_err(|error| match error.get_transaction_error() {
+                Some(TransactionError::InstructionError(_, error)) => {
+                    match instruction_error(&error) {
+                        Some(program_error) => anyhow!("Transaction failed: {program_error}"),
+                        None => anyhow!("Transaction failed: {error}"),
+                    }
+                }
+                _ => anyhow!(error),
+            })?;
+        println!("Signature: {signature}");
+        Ok(())
+    }
+}
+
+/// The Solana CLI's default keypair
+fn default_keypair_path() -> Result<PathBuf> {
+    let home = std::env::var_os("HOME").context("Pass --keypair, as HOME isn't set")?;
+    Ok(PathBuf::from(home).join(".config/solana/id.json"))
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Result;
+use capstone::DAY_IN_SECONDS;
+use capstone_client::{MeditationPlan, PlanStatus, ProjectedSettlement};
+
+/// What support staff need to know about a plan at a point in time
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanSummary {
+    pub plan: Pubkey,
+    pub owner: Pubkey,
+    pub beneficiary: Pubkey,
+    pub status: PlanStatus,
+    pub version: u8,
+    pub start_at: i64,
+    pub end_at: i64,
+    pub now: i64,
+    pub sessions_today: u64,
+    pub daily_frequency: u8,
+    pub sessions_completed: u64,
+    pub commitment_stake: u64,
+    pub settlement: ProjectedSettlement,
+}
+
+impl PlanSummary {
+    pub fn new(address: Pubkey, plan: &MeditationPlan, now: i64) -> Result<Self> {
+        Ok(Self {
+            plan: address,
+            owner: plan.owner,
+            beneficiary: plan.beneficiary,
+            status: plan.status_at(now),
+            version: plan.version,
+            start_at: plan.start_at,
+            end_at: plan.end_at,
+            now,
+            sessions_today: sessions_today(plan, now),
+            daily_frequency: plan.daily_frequency,
+            sessions_completed: plan.sessions_completed(),
+            commitment_stake: plan.commitment_stake,
+            settlement: plan.projected_settlement(now)?,
+        })
+    }
+}
+
+/// Sessions attested on the plan day `now` falls in - plan days start at `start_at`, not midnight
+pub fn sessions_today(plan: &MeditationPlan, now: i64) -> u64 {
+    if now < plan.start_at || now > plan.end_at {
+        return 0;
+    }
+    let day_start = plan.start_at + (now - plan.start_at) / DAY_IN_SECONDS * DAY_IN_SECONDS;
+    let day_end = day_start + DAY_IN_SECONDS;
+    plan.attestations
+        .iter()
+        .filter(|attestation| {
+            attestation.started_at >= day_start && attestation.started_at < day_end
+        })
+        .count() as u64
+}
+
+/// Formats a number of seconds as days, hours and minutes
+fn format_duration(seconds: i64) -> String {
+    let seconds = seconds.unsigned_abs();
+    let (days, hours, minutes) = (
+        seconds / DAY_IN_SECONDS as u64,
+        seconds % DAY_IN_SECONDS as u64 / 3_600,
+        seconds % 3_600 / 60,
+    );
+    match (days, hours) {
+        (0, 0) => format!("{minutes}m"),
+        (0, _) => format!("{hours}h {minutes}m"),
+        _ => format!("{days}d {hours}h"),
+    }
+}
+
+fn format_relative(at: i64, now: i64) -> String {
+    if at >= now {
+        format!("{at} (in {})", format_duration(at - now))
+    } else {
+        format!("{at} ({} ago)", format_duration(now - at))
+    }
+}
+
+impl fmt::Display for PlanSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Plan:                {}", self.plan)?;
+        writeln!(f, "Owner:               {}", self.owner)?;
+        writeln!(f, "Beneficiary:         {}", self.beneficiary)?;
+        write!(f, "Status:              {:?}", self.status)?;
+        if self.version < MeditationPlan::LAYOUT_VERSION {
+            write!(f, " (legacy layout - run migrate-plan)")?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Starts at:           {}",
+            format_relative(self.start_at, self.now)
+        )?;
+        writeln!(
+            f,
+            "Ends at:             {}",
+            format_relative(self.end_at, self.now)
+        )?;
+        writeln!(
+            f,
+            "Sessions today:      {}/{}",
+            self.sessions_today, self.daily_frequency
+        )?;
+        writeln!(
+            f,
+            "Sessions completed:  {}/{}",
+            self.sessions_completed,
+            self.sessions_completed + self.settlement.remaining_sessions
+        )?;
+        writeln!(
+            f,
+            "Sessions remaining:  {}",
+            self.settlement.remaining_sessions
+        )?;
+        writeln!(
+            f,
+            "Sessions missed:     {}",
+            self.settlement.missed_sessions
+        )?;
+        writeln!(f, "Commitment stake:    {}", self.commitment_stake)?;
+        writeln!(f, "Projected penalties: {}", self.settlement.penalties)?;
+        write!(
+            f,
+            "Projected payout:    {} (excluding yield)",
+            self.settlement.rewards
+        )
+    }
+}
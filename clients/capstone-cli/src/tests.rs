@@ -0,0 +1,80 @@
+use anchor_lang::prelude::Pubkey;
+use capstone_client::{MeditationAttestation, MeditationPlan, PlanStatus};
+
+use crate::summary::{sessions_today, PlanSummary};
+
+const DAY: i64 = 24 * 60 * 60;
+const STAKE: u64 = 50_000_000;
+
+/// A 7-day plan with two sessions a day starting at 0, and a 50% penalty
+fn plan_fixture() -> MeditationPlan {
+    let owner = Pubkey::new_unique();
+    let mut plan = MeditationPlan::new(owner, owner, 1, 255, 7, 2, 20, STAKE, 0, Pubkey::default());
+    plan.penalty_bps = 5_000;
+    plan
+}
+
+fn attest(plan: &mut MeditationPlan, started_at: i64) {
+    plan.attestations.push(MeditationAttestation {
+        attester: plan.owner,
+        started_at,
+        ended_at: started_at + 20 * 60,
+    });
+    plan.rewards += plan.reward_per_session;
+}
+
+#[test]
+fn test_sessions_today_counts_current_plan_day() {
+    let mut plan = plan_fixture();
+    attest(&mut plan, 60);
+    attest(&mut plan, DAY + 60);
+    attest(&mut plan, DAY + 2 * 60 * 60);
+
+    assert_eq!(sessions_today(&plan, 2 * 60 * 60), 1);
+    assert_eq!(sessions_today(&plan, DAY + 3 * 60 * 60), 2);
+    assert_eq!(sessions_today(&plan, 2 * DAY), 0);
+    assert_eq!(
+        sessions_today(&plan, plan.end_at + 1),
+        0,
+        "Ended plans have no plan day"
+    );
+}
+
+#[test]
+fn test_plan_summary() {
+    let mut plan = plan_fixture();
+    attest(&mut plan, 60);
+    let address = Pubkey::new_unique();
+
+    let summary = PlanSummary::new(address, &plan, DAY + 60 * 60).unwrap();
+    assert_eq!(summary.plan, address);
+    assert_eq!(summary.status, PlanStatus::Active);
+    assert_eq!(summary.sessions_today, 0);
+    assert_eq!(summary.sessions_completed, 1);
+    assert_eq!(summary.settlement.remaining_sessions, 13);
+    assert_eq!(
+        summary.settlement.missed_sessions, 1,
+        "The second session of the first day was missed"
+    );
+    assert_eq!(
+        summary.settlement.rewards,
+        STAKE - summary.settlement.penalties
+    );
+
+    let summary = PlanSummary::new(address, &plan, plan.end_at + 1).unwrap();
+    assert_eq!(summary.status, PlanStatus::PendingSettlement);
+    assert_eq!(summary.settlement.missed_sessions, 13);
+
+    plan.status = PlanStatus::Completed;
+    plan.rewards = 1_000;
+    plan.penalties = 2_000;
+    let summary = PlanSummary::new(address, &plan, plan.end_at + 1).unwrap();
+    assert_eq!(
+        summary.settlement.rewards, 1_000,
+        "Settled plans report what they paid out"
+    );
+    assert_eq!(summary.settlement.penalties, 2_000);
+    assert!(summary
+        .to_string()
+        .contains("Status:              Completed"));
+}
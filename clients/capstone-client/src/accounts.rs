@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use capstone::{
+    Charity, Config, MeditationPlan, MeditationPlanV0, PlanTemplate, Practitioner,
+    ProjectedSettlement,
+};
+
+/// Decodes any capstone account from its raw data, checking the discriminator
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Decodes a plan in either layout. Plans still in the legacy layout come back with `version` 0,
+/// as they need `migrate_plan` before the program accepts them.
+pub fn meditation_plan(data: &[u8]) -> Result<MeditationPlan> {
+    if data.len() != MeditationPlanV0::SPACE {
+        return decode(data);
+    }
+
+    require!(
+        data.starts_with(MeditationPlan::DISCRIMINATOR),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    let mut body = &data[MeditationPlan::DISCRIMINATOR.len()..];
+    let legacy = MeditationPlanV0::deserialize(&mut body)
+        .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+    let mut plan = MeditationPlan::from(legacy);
+    plan.version = 0;
+    Ok(plan)
+}
+
+pub fn practitioner(data: &[u8]) -> Result<Practitioner> {
+    decode(data)
+}
+
+pub fn config(data: &[u8]) -> Result<Config> {
+    decode(data)
+}
+
+pub fn plan_template(data: &[u8]) -> Result<PlanTemplate> {
+    decode(data)
+}
+
+pub fn charity(data: &[u8]) -> Result<Charity> {
+    decode(data)
+}
+
+/// Decodes the return data of `preview_settlement`
+pub fn projected_settlement(return_data: &[u8]) -> Result<ProjectedSettlement> {
+    ProjectedSettlement::try_from_slice(return_data)
+        .map_err(|_| ErrorCode::InstructionDidNotDeserialize.into())
+}
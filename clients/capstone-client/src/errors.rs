@@ -0,0 +1,66 @@
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::solana_program::instruction::InstructionError;
+
+use capstone::error::MeditationPlanError;
+
+/// Every program error in declaration order - Anchor numbers them from `ERROR_CODE_OFFSET`, so new
+/// variants are appended here as they are to the enum
+pub(crate) const PROGRAM_ERRORS: &[MeditationPlanError] = {
+    use MeditationPlanError::*;
+    &[
+        AttestationTooLong,
+        AttestationTooShort,
+        DailyFrequencyExceeded,
+        InvalidCommitmentStakeAmount,
+        InvalidDailyFrequency,
+        InvalidDurationMinutes,
+        InvalidMint,
+        InvalidNumberOfDays,
+        InvalidTimestamps,
+        PlanCompleted,
+        PlanExpired,
+        PlanInactive,
+        PlanNotEnded,
+        PlanNotStarted,
+        UnauthorizedAccess,
+        BadgeAlreadyMinted,
+        PlanNotCompleted,
+        InvalidBasisPoints,
+        InvalidLendingProgram,
+        UnauthorizedAdmin,
+        PauseLimitExceeded,
+        PlanNotPaused,
+        NoRemainingSessions,
+        GracePeriodActive,
+        InvalidBeneficiary,
+        InvalidTemplateName,
+        InvalidWithdrawalAmount,
+        InvalidCharity,
+        InvalidCharityName,
+        DisputeWindowActive,
+        DisputeWindowClosed,
+        UnauthorizedOracle,
+        InvalidPlanLayout,
+        PlanAlreadyMigrated,
+        PlanNotMigrated,
+        TemplatePenaltyTooLow,
+        BadgeNotEarned,
+        InvalidStatusTransition,
+        InvalidStartTime,
+    ]
+};
+
+/// Maps a custom error code returned by the program to its error - None for Anchor's own errors
+/// and codes the program doesn't define
+pub fn program_error(code: u32) -> Option<MeditationPlanError> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    PROGRAM_ERRORS.get(index as usize).copied()
+}
+
+/// Maps a failed instruction to the program error that caused it, if any
+pub fn instruction_error(error: &InstructionError) -> Option<MeditationPlanError> {
+    match error {
+        InstructionError::Custom(code) => program_error(*code),
+        _ => None,
+    }
+}
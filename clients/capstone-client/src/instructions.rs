@@ -0,0 +1,553 @@
+//! Typed builders for every capstone instruction. PDAs, ATAs and program accounts are derived, so
+//! callers only pass the wallets, mint and plan the instruction is about.
+//!
+//! Plans using a lending program also need the adapter's accounts appended to
+//! `Instruction::accounts` for `initialize`, `increase_stake`, `cancel_plan` and the settlement
+//! instructions.
+//! `renew` takes the finished plan's adapter accounts followed by the renewed plan's.
+//! `lending_accounts` builds them.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::{associated_token, token, token_2022};
+
+use capstone::{accounts, instruction, CharityKind};
+
+use crate::pda;
+
+/// Length, frequency and stake of a new plan - the `initialize` and `renew` arguments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlanParams {
+    pub number_of_days: u8,
+    pub daily_frequency: u8,
+    pub duration_minutes: u8,
+    pub commitment_stake: u64,
+}
+
+/// Fees, lending program and attestation oracle set by the admin - the config arguments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConfigParams {
+    pub lending_program: Pubkey,
+    pub owner_yield_bps: u16,
+    pub penalty_fee_bps: u16,
+    pub yield_fee_bps: u16,
+    pub attestation_oracle: Pubkey,
+}
+
+/// Adapter accounts a lending plan's instructions take as remaining accounts:
+/// `[lending_program, pool, position, reserve]`
+pub fn lending_accounts(
+    lending_program: Pubkey,
+    mint: Pubkey,
+    meditation_plan: Pubkey,
+) -> Vec<AccountMeta> {
+    let pool = pda::lending_pool(&lending_program, &mint);
+    vec![
+        AccountMeta::new_readonly(lending_program, false),
+        AccountMeta::new(pool, false),
+        AccountMeta::new(
+            pda::lending_position(&lending_program, &pool, &meditation_plan),
+            false,
+        ),
+        AccountMeta::new(pda::token_account(&pool, &mint), false),
+    ]
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: capstone::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// The charity, its wallet and its ATA a settlement needs, from the wallet of the plan's charity
+fn settlement_charity(
+    charity_wallet: Option<Pubkey>,
+    mint: &Pubkey,
+) -> (Option<Pubkey>, Option<Pubkey>, Option<Pubkey>) {
+    match charity_wallet {
+        Some(wallet) => (
+            Some(pda::charity(&wallet).0),
+            Some(wallet),
+            Some(pda::token_account(&wallet, mint)),
+        ),
+        None => (None, None, None),
+    }
+}
+
+fn initialize_accounts(
+    owner: Pubkey,
+    owner_ata: Pubkey,
+    mint: Pubkey,
+    id: u64,
+    charity: Option<Pubkey>,
+) -> accounts::Initialize {
+    let meditation_plan = pda::meditation_plan(&owner, id).0;
+    accounts::Initialize {
+        owner,
+        meditation_plan,
+        config: pda::config().0,
+        charity,
+        mint,
+        owner_ata,
+        vault: pda::vault(&meditation_plan, &mint),
+        associated_token_program: associated_token::ID,
+        token_program: token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+/// Creates a plan and moves the stake into its vault. `charity` is the registry account of an
+/// approved charity or anti-charity receiving the penalties, and `start_at` schedules the plan to
+/// start later instead of right away.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize(
+    owner: Pubkey,
+    owner_ata: Pubkey,
+    mint: Pubkey,
+    id: u64,
+    params: PlanParams,
+    beneficiary: Option<Pubkey>,
+    charity: Option<Pubkey>,
+    start_at: Option<i64>,
+) -> Instruction {
+    build(
+        initialize_accounts(owner, owner_ata, mint, id, charity),
+        instruction::Initialize {
+            id,
+            number_of_days: params.number_of_days,
+            daily_frequency: params.daily_frequency,
+            duration_minutes: params.duration_minutes,
+            commitment_stake: params.commitment_stake,
+            beneficiary,
+            start_at,
+        },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_plan_template(
+    creator: Pubkey,
+    mint: Pubkey,
+    id: u64,
+    name: String,
+    number_of_days: u8,
+    daily_frequency: u8,
+    duration_minutes: u8,
+    suggested_stake: u64,
+    penalty_bps: u16,
+) -> Instruction {
+    build(
+        accounts::CreatePlanTemplate {
+            creator,
+            plan_template: pda::plan_template(&creator, id).0,
+            mint,
+            system_program: system_program::ID,
+        },
+        instruction::CreatePlanTemplate {
+            id,
+            name,
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            suggested_stake,
+            penalty_bps,
+        },
+    )
+}
+
+/// Creates a plan from a template, staking the suggested stake unless `commitment_stake` is set
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_from_template(
+    owner: Pubkey,
+    owner_ata: Pubkey,
+    mint: Pubkey,
+    plan_template: Pubkey,
+    id: u64,
+    commitment_stake: Option<u64>,
+    beneficiary: Option<Pubkey>,
+    charity: Option<Pubkey>,
+) -> Instruction {
+    build(
+        accounts::InitializeFromTemplate {
+            plan_template,
+            initialize: initialize_accounts(owner, owner_ata, mint, id, charity),
+        },
+        instruction::InitializeFromTemplate {
+            id,
+            commitment_stake,
+            beneficiary,
+        },
+    )
+}
+
+/// Attests a session - the practitioner account always belongs to the plan `owner`, even when
+/// someone else attests
+pub fn attest(
+    attester: Pubkey,
+    owner: Pubkey,
+    meditation_plan: Pubkey,
+    started_at: i64,
+    ended_at: i64,
+) -> Instruction {
+    build(
+        accounts::Attest {
+            attester,
+            meditation_plan,
+            practitioner: pda::practitioner(&owner).0,
+            system_program: system_program::ID,
+        },
+        instruction::Attest {
+            started_at,
+            ended_at,
+        },
+    )
+}
+
+/// Attests a session during the dispute window - must be signed by the owner and the oracle
+pub fn submit_late_attestation(
+    owner: Pubkey,
+    oracle: Pubkey,
+    meditation_plan: Pubkey,
+    started_at: i64,
+    ended_at: i64,
+) -> Instruction {
+    build(
+        accounts::SubmitLateAttestation {
+            owner,
+            oracle,
+            meditation_plan,
+            config: pda::config().0,
+            practitioner: pda::practitioner(&owner).0,
+            system_program: system_program::ID,
+        },
+        instruction::SubmitLateAttestation {
+            started_at,
+            ended_at,
+        },
+    )
+}
+
+/// Settles the plan and pays out to the beneficiary. `charity_wallet` is the wallet of the
+/// plan's charity, if it chose one.
+pub fn complete(
+    owner: Pubkey,
+    mint: Pubkey,
+    meditation_plan: Pubkey,
+    beneficiary: Pubkey,
+    beneficiary_ata: Pubkey,
+    charity_wallet: Option<Pubkey>,
+) -> Instruction {
+    let (charity, charity_wallet, charity_ata) = settlement_charity(charity_wallet, &mint);
+    build(
+        accounts::Complete {
+            owner,
+            meditation_plan,
+            practitioner: pda::practitioner(&owner).0,
+            config: pda::config().0,
+            mint,
+            beneficiary,
+            beneficiary_ata,
+            vault: pda::vault(&meditation_plan, &mint),
+            treasury: pda::treasury(&mint),
+            charity,
+            charity_wallet,
+            charity_ata,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Complete {},
+    )
+}
+
+pub fn increase_stake(
+    owner: Pubkey,
+    owner_ata: Pubkey,
+    mint: Pubkey,
+    meditation_plan: Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::IncreaseStake {
+            owner,
+            meditation_plan,
+            mint,
+            owner_ata,
+            vault: pda::vault(&meditation_plan, &mint),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::IncreaseStake { amount },
+    )
+}
+
+pub fn extend_plan(owner: Pubkey, meditation_plan: Pubkey, additional_days: u8) -> Instruction {
+    build(
+        accounts::ExtendPlan {
+            owner,
+            meditation_plan,
+        },
+        instruction::ExtendPlan { additional_days },
+    )
+}
+
+pub fn pause_plan(owner: Pubkey, meditation_plan: Pubkey) -> Instruction {
+    build(
+        accounts::PausePlan {
+            owner,
+            meditation_plan,
+        },
+        instruction::PausePlan {},
+    )
+}
+
+pub fn resume_plan(owner: Pubkey, meditation_plan: Pubkey) -> Instruction {
+    build(
+        accounts::ResumePlan {
+            owner,
+            meditation_plan,
+        },
+        instruction::ResumePlan {},
+    )
+}
+
+/// Cancels a plan before it starts and refunds its vault to the owner
+pub fn cancel_plan(owner: Pubkey, mint: Pubkey, meditation_plan: Pubkey) -> Instruction {
+    build(
+        accounts::CancelPlan {
+            owner,
+            meditation_plan,
+            mint,
+            owner_ata: pda::token_account(&owner, &mint),
+            vault: pda::vault(&meditation_plan, &mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CancelPlan {},
+    )
+}
+
+/// Closes a completed or cancelled plan and its vault
+pub fn close_plan(owner: Pubkey, mint: Pubkey, meditation_plan: Pubkey) -> Instruction {
+    build(
+        accounts::ClosePlan {
+            owner,
+            meditation_plan,
+            mint,
+            owner_ata: pda::token_account(&owner, &mint),
+            vault: pda::vault(&meditation_plan, &mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClosePlan {},
+    )
+}
+
+/// Settles the plan and rolls the payout into a new plan with id `new_id`
+#[allow(clippy::too_many_arguments)]
+pub fn renew(
+    owner: Pubkey,
+    owner_ata: Pubkey,
+    mint: Pubkey,
+    meditation_plan: Pubkey,
+    beneficiary: Pubkey,
+    new_id: u64,
+    params: PlanParams,
+    charity_wallet: Option<Pubkey>,
+) -> Instruction {
+    let new_meditation_plan = pda::meditation_plan(&owner, new_id).0;
+    let (charity, charity_wallet, charity_ata) = settlement_charity(charity_wallet, &mint);
+    build(
+        accounts::Renew {
+            owner,
+            meditation_plan,
+            new_meditation_plan,
+            practitioner: pda::practitioner(&owner).0,
+            config: pda::config().0,
+            mint,
+            owner_ata,
+            beneficiary,
+            beneficiary_ata: pda::token_account(&beneficiary, &mint),
+            vault: pda::vault(&meditation_plan, &mint),
+            treasury: pda::treasury(&mint),
+            charity,
+            charity_wallet,
+            charity_ata,
+            new_vault: pda::vault(&new_meditation_plan, &mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Renew {
+            new_id,
+            number_of_days: params.number_of_days,
+            daily_frequency: params.daily_frequency,
+            duration_minutes: params.duration_minutes,
+            commitment_stake: params.commitment_stake,
+        },
+    )
+}
+
+/// Settles a plan its owner never came back to, paid for by the keeper
+pub fn settle_expired(
+    keeper: Pubkey,
+    owner: Pubkey,
+    mint: Pubkey,
+    meditation_plan: Pubkey,
+    beneficiary: Pubkey,
+    charity_wallet: Option<Pubkey>,
+) -> Instruction {
+    let (charity, charity_wallet, charity_ata) = settlement_charity(charity_wallet, &mint);
+    build(
+        accounts::SettleExpired {
+            keeper,
+            owner,
+            meditation_plan,
+            practitioner: pda::practitioner(&owner).0,
+            config: pda::config().0,
+            mint,
+            beneficiary,
+            beneficiary_ata: pda::token_account(&beneficiary, &mint),
+            vault: pda::vault(&meditation_plan, &mint),
+            treasury: pda::treasury(&mint),
+            charity,
+            charity_wallet,
+            charity_ata,
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::SettleExpired {},
+    )
+}
+
+pub fn mint_badge(owner: Pubkey, meditation_plan: Pubkey) -> Instruction {
+    let badge_mint = pda::badge_mint(&meditation_plan).0;
+    build(
+        accounts::MintBadge {
+            owner,
+            meditation_plan,
+            badge_mint,
+            owner_badge_ata: pda::badge_account(&owner, &badge_mint),
+            associated_token_program: associated_token::ID,
+            token_program: token_2022::ID,
+            system_program: system_program::ID,
+        },
+        instruction::MintBadge {},
+    )
+}
+
+/// Upgrades a plan in the legacy layout - anyone can pay for it
+pub fn migrate_plan(payer: Pubkey, meditation_plan: Pubkey) -> Instruction {
+    build(
+        accounts::MigratePlan {
+            payer,
+            meditation_plan,
+            system_program: system_program::ID,
+        },
+        instruction::MigratePlan {},
+    )
+}
+
+/// Previews what the plan would settle to - simulate it and decode the return data with
+/// `accounts::projected_settlement`
+pub fn preview_settlement(meditation_plan: Pubkey) -> Instruction {
+    build(
+        accounts::PreviewSettlement { meditation_plan },
+        instruction::PreviewSettlement {},
+    )
+}
+
+/// Creates the config - must be signed by the program's upgrade authority
+pub fn initialize_config(admin: Pubkey, params: ConfigParams) -> Instruction {
+    build(
+        accounts::InitializeConfig {
+            admin,
+            config: pda::config().0,
+            program: capstone::ID,
+            program_data: pda::program_data(),
+            system_program: system_program::ID,
+        },
+        instruction::InitializeConfig {
+            lending_program: params.lending_program,
+            owner_yield_bps: params.owner_yield_bps,
+            penalty_fee_bps: params.penalty_fee_bps,
+            yield_fee_bps: params.yield_fee_bps,
+            attestation_oracle: params.attestation_oracle,
+        },
+    )
+}
+
+pub fn update_config(admin: Pubkey, params: ConfigParams) -> Instruction {
+    build(
+        accounts::UpdateConfig {
+            admin,
+            config: pda::config().0,
+        },
+        instruction::UpdateConfig {
+            lending_program: params.lending_program,
+            owner_yield_bps: params.owner_yield_bps,
+            penalty_fee_bps: params.penalty_fee_bps,
+            yield_fee_bps: params.yield_fee_bps,
+            attestation_oracle: params.attestation_oracle,
+        },
+    )
+}
+
+pub fn register_charity(
+    admin: Pubkey,
+    wallet: Pubkey,
+    mint: Pubkey,
+    name: String,
+    kind: CharityKind,
+) -> Instruction {
+    build(
+        accounts::RegisterCharity {
+            admin,
+            config: pda::config().0,
+            wallet,
+            charity: pda::charity(&wallet).0,
+            mint,
+            charity_ata: pda::token_account(&wallet, &mint),
+            associated_token_program: associated_token::ID,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterCharity { name, kind },
+    )
+}
+
+pub fn update_charity(admin: Pubkey, charity: Pubkey, is_active: bool) -> Instruction {
+    build(
+        accounts::UpdateCharity {
+            admin,
+            config: pda::config().0,
+            charity,
+        },
+        instruction::UpdateCharity { is_active },
+    )
+}
+
+pub fn withdraw_treasury(
+    admin: Pubkey,
+    mint: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::WithdrawTreasury {
+            admin,
+            config: pda::config().0,
+            mint,
+            treasury: pda::treasury(&mint),
+            destination,
+            token_program: token::ID,
+        },
+        instruction::WithdrawTreasury { amount },
+    )
+}
@@ -0,0 +1,16 @@
+//! Rust client for the capstone program - typed instruction builders, PDA derivation, account
+//! decoders and program error mapping for backends, bots and the program's own tests.
+
+pub mod accounts;
+pub mod errors;
+pub mod instructions;
+pub mod pda;
+
+pub use capstone::error::MeditationPlanError;
+pub use capstone::{
+    Charity, CharityKind, Config, MeditationAttestation, MeditationPlan, PlanStatus, PlanTemplate,
+    Practitioner, ProjectedSettlement, ID as PROGRAM_ID,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::{token, token_2022};
+
+fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &capstone::ID)
+}
+
+pub fn config() -> (Pubkey, u8) {
+    find(&[b"config"])
+}
+
+pub fn meditation_plan(owner: &Pubkey, id: u64) -> (Pubkey, u8) {
+    find(&[
+        b"meditation_plan",
+        owner.as_ref(),
+        id.to_le_bytes().as_ref(),
+    ])
+}
+
+/// The plan's vault is its ATA for the stake mint
+pub fn vault(meditation_plan: &Pubkey, mint: &Pubkey) -> Pubkey {
+    token_account(meditation_plan, mint)
+}
+
+pub fn practitioner(owner: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"practitioner", owner.as_ref()])
+}
+
+pub fn plan_template(creator: &Pubkey, id: u64) -> (Pubkey, u8) {
+    find(&[
+        b"plan_template",
+        creator.as_ref(),
+        id.to_le_bytes().as_ref(),
+    ])
+}
+
+pub fn charity(wallet: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"charity", wallet.as_ref()])
+}
+
+/// The treasury collecting protocol fees is the config PDA's ATA
+pub fn treasury(mint: &Pubkey) -> Pubkey {
+    token_account(&config().0, mint)
+}
+
+pub fn badge_mint(meditation_plan: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"badge", meditation_plan.as_ref()])
+}
+
+/// The owner's Token-2022 account holding the completion badge
+pub fn badge_account(owner: &Pubkey, badge_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, badge_mint, &token_2022::ID)
+}
+
+/// Pool of the lending program for a mint, in the mock-lending layout the program's adapter uses
+pub fn lending_pool(lending_program: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool", mint.as_ref()], lending_program).0
+}
+
+/// The plan's position in a lending pool
+pub fn lending_position(
+    lending_program: &Pubkey,
+    pool: &Pubkey,
+    meditation_plan: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"position", pool.as_ref(), meditation_plan.as_ref()],
+        lending_program,
+    )
+    .0
+}
+
+/// Program data account of the upgradeable program, whose upgrade authority creates the config
+pub fn program_data() -> Pubkey {
+    Pubkey::find_program_address(&[capstone::ID.as_ref()], &bpf_loader_upgradeable::ID).0
+}
+
+/// ATA of the wallet for a mint under the SPL Token program, which USDC uses
+pub fn token_account(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(wallet, mint, &token::ID)
+}
@@ -0,0 +1,185 @@
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::InstructionError;
+use anchor_lang::{AnchorSerialize, Discriminator, Space};
+
+use capstone::{MeditationPlan, MeditationPlanV0, PlanStatus};
+
+use crate::errors::{instruction_error, program_error, PROGRAM_ERRORS};
+use crate::instructions::{self, PlanParams};
+use crate::{accounts, pda, MeditationPlanError};
+
+fn plan_fixture(owner: Pubkey) -> MeditationPlan {
+    MeditationPlan::new(
+        owner,
+        owner,
+        1,
+        255,
+        7,
+        1,
+        20,
+        50_000_000,
+        0,
+        Pubkey::default(),
+    )
+}
+
+#[test]
+fn test_program_errors_match_codes() {
+    for (index, error) in PROGRAM_ERRORS.iter().enumerate() {
+        assert_eq!(
+            u32::from(*error),
+            ERROR_CODE_OFFSET + index as u32,
+            "{} is out of order",
+            error.name()
+        );
+    }
+    assert_eq!(
+        u32::from(*PROGRAM_ERRORS.last().unwrap()),
+        u32::from(MeditationPlanError::InvalidStartTime),
+        "Errors declared after the last listed one are missing"
+    );
+}
+
+#[test]
+fn test_baseline_error_codes_are_stable() {
+    // Codes returned by the first deployment, which existing clients already match on
+    assert_eq!(
+        u32::from(MeditationPlanError::AttestationTooLong),
+        ERROR_CODE_OFFSET
+    );
+    assert_eq!(
+        u32::from(MeditationPlanError::InvalidMint),
+        ERROR_CODE_OFFSET + 6
+    );
+    assert_eq!(
+        u32::from(MeditationPlanError::UnauthorizedAccess),
+        ERROR_CODE_OFFSET + 14
+    );
+}
+
+#[test]
+fn test_program_error_mapping() {
+    let code = u32::from(MeditationPlanError::PlanExpired);
+    assert_eq!(
+        program_error(code).map(u32::from),
+        Some(code),
+        "Program codes should map back to their error"
+    );
+    assert_eq!(
+        instruction_error(&InstructionError::Custom(code)).map(u32::from),
+        Some(code)
+    );
+
+    // Anchor's own errors and unknown codes aren't program errors
+    assert!(program_error(2003).is_none());
+    assert!(program_error(ERROR_CODE_OFFSET + PROGRAM_ERRORS.len() as u32).is_none());
+    assert!(instruction_error(&InstructionError::MissingRequiredSignature).is_none());
+}
+
+#[test]
+fn test_builders_derive_plan_accounts() {
+    let owner = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let owner_ata = pda::token_account(&owner, &mint);
+    let params = PlanParams {
+        number_of_days: 7,
+        daily_frequency: 1,
+        duration_minutes: 20,
+        commitment_stake: 50_000_000,
+    };
+
+    let instruction = instructions::initialize(owner, owner_ata, mint, 3, params, None, None, None);
+    let (meditation_plan, _bump) = pda::meditation_plan(&owner, 3);
+    assert_eq!(instruction.program_id, capstone::ID);
+    assert_eq!(instruction.accounts[1].pubkey, meditation_plan);
+    assert_eq!(
+        instruction.accounts[3].pubkey,
+        capstone::ID,
+        "A missing charity is passed as the program ID"
+    );
+    assert_eq!(
+        instruction.accounts[6].pubkey,
+        pda::vault(&meditation_plan, &mint)
+    );
+    assert!(instruction
+        .data
+        .starts_with(capstone::instruction::Initialize::DISCRIMINATOR));
+
+    let instruction = instructions::attest(owner, owner, meditation_plan, 0, 1_200);
+    assert_eq!(instruction.accounts[2].pubkey, pda::practitioner(&owner).0);
+    assert_eq!(
+        instruction.data.len(),
+        capstone::instruction::Attest::DISCRIMINATOR.len() + 16
+    );
+
+    let charity_wallet = Pubkey::new_unique();
+    let instruction = instructions::complete(
+        owner,
+        mint,
+        meditation_plan,
+        owner,
+        owner_ata,
+        Some(charity_wallet),
+    );
+    assert_eq!(instruction.accounts[8].pubkey, pda::treasury(&mint));
+    assert_eq!(
+        instruction.accounts[9].pubkey,
+        pda::charity(&charity_wallet).0
+    );
+    assert_eq!(instruction.accounts[10].pubkey, charity_wallet);
+    assert_eq!(
+        instruction.accounts[11].pubkey,
+        pda::token_account(&charity_wallet, &mint)
+    );
+}
+
+#[test]
+fn test_decode_meditation_plan() {
+    let plan = plan_fixture(Pubkey::new_unique());
+    let mut data = MeditationPlan::DISCRIMINATOR.to_vec();
+    data.extend(plan.try_to_vec().unwrap());
+    data.resize(
+        MeditationPlan::DISCRIMINATOR.len() + MeditationPlan::INIT_SPACE,
+        0,
+    );
+
+    let decoded = accounts::meditation_plan(&data).expect("Plan should decode");
+    assert_eq!(decoded.owner, plan.owner);
+    assert_eq!(decoded.status, PlanStatus::Active);
+    assert!(decoded.is_current_layout());
+
+    assert!(
+        accounts::practitioner(&data).is_err(),
+        "Decoding as another account should fail the discriminator check"
+    );
+}
+
+#[test]
+fn test_decode_legacy_meditation_plan() {
+    let plan = plan_fixture(Pubkey::new_unique());
+
+    // The layout before versioning, field by field
+    let mut data = MeditationPlan::DISCRIMINATOR.to_vec();
+    data.extend(0u32.to_le_bytes());
+    data.push(plan.bump);
+    data.extend(plan.commitment_stake.to_le_bytes());
+    data.push(plan.daily_frequency);
+    data.push(plan.duration_minutes);
+    data.extend(plan.end_at.to_le_bytes());
+    data.extend(plan.id.to_le_bytes());
+    data.extend([1, 0]);
+    data.push(plan.number_of_days);
+    data.extend(plan.owner.to_bytes());
+    data.extend(0u64.to_le_bytes());
+    data.extend(0u64.to_le_bytes());
+    data.extend(plan.start_at.to_le_bytes());
+    data.resize(MeditationPlanV0::SPACE, 0);
+
+    let decoded = accounts::meditation_plan(&data).expect("Legacy plan should decode");
+    assert_eq!(decoded.owner, plan.owner);
+    assert_eq!(decoded.commitment_stake, plan.commitment_stake);
+    assert_eq!(decoded.end_at, plan.end_at);
+    assert_eq!(decoded.status, PlanStatus::Active);
+    assert_eq!(decoded.version, 0, "Legacy plans still need migrating");
+}
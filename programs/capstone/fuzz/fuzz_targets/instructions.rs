@@ -0,0 +1,479 @@
+//! Sends arbitrary `initialize`, `attest` and `complete` instructions to the deployed program under
+//! LiteSVM - extreme arguments, random instruction data, swapped accounts and the wrong mint -
+//! and checks the program never panics and tokens only move with their owner's signature.
+//!
+//! Needs `anchor build` to have produced `target/deploy/capstone.so`.
+
+#![no_main]
+
+use std::collections::HashMap;
+
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{AccountSerialize, Discriminator};
+use arbitrary::Arbitrary;
+use capstone::{Config, PlanStatus, USDC_MINT};
+use capstone_client::instructions::{self, PlanParams};
+use capstone_client::{accounts, pda};
+use libfuzzer_sys::fuzz_target;
+use litesvm::LiteSVM;
+use solana_account::Account;
+use solana_clock::Clock;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+use spl_token::solana_program::program_option::COption;
+use spl_token::solana_program::program_pack::Pack;
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+
+const PROGRAM_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../../target/deploy/capstone.so"
+);
+const START_TIME: i64 = 1_700_000_000;
+const WALLET_TOKENS: u64 = 1_000_000_000;
+const PLAN_IDS: u64 = 4;
+
+#[derive(Arbitrary, Debug)]
+struct Step {
+    instruction: FuzzInstruction,
+    /// Swaps the keys of two of the instruction's accounts
+    swap: Option<(u8, u8)>,
+}
+
+/// Indexes pick from the wallets, mints, plans and token accounts the harness knows about
+#[derive(Arbitrary, Debug)]
+enum FuzzInstruction {
+    Initialize {
+        owner: u8,
+        owner_ata: u8,
+        mint: u8,
+        id: u8,
+        number_of_days: u8,
+        daily_frequency: u8,
+        duration_minutes: u8,
+        commitment_stake: u64,
+        beneficiary: Option<u8>,
+        start_at: Option<i64>,
+    },
+    Attest {
+        attester: u8,
+        plan: u8,
+        started_at: i64,
+        ended_at: i64,
+    },
+    Complete {
+        owner: u8,
+        plan: u8,
+        mint: u8,
+        beneficiary: u8,
+        beneficiary_ata: u8,
+    },
+    /// A well-formed instruction with its arguments replaced by arbitrary bytes
+    RawData {
+        kind: u8,
+        signer: u8,
+        plan: u8,
+        data: Vec<u8>,
+    },
+    Warp {
+        seconds: u32,
+    },
+}
+
+struct Harness {
+    svm: LiteSVM,
+    wallets: Vec<Keypair>,
+    mints: Vec<Pubkey>,
+}
+
+impl Harness {
+    fn wallet(&self, index: u8) -> &Keypair {
+        &self.wallets[index as usize % self.wallets.len()]
+    }
+
+    fn mint(&self, index: u8) -> Pubkey {
+        self.mints[index as usize % self.mints.len()]
+    }
+
+    /// Every plan the wallets can create, with its owner
+    fn plans(&self) -> Vec<(Pubkey, Pubkey)> {
+        self.wallets
+            .iter()
+            .flat_map(|wallet| {
+                (0..PLAN_IDS).map(|id| {
+                    (
+                        pda::meditation_plan(&wallet.pubkey(), id).0,
+                        wallet.pubkey(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn plan(&self, index: u8) -> (Pubkey, Pubkey) {
+        let plans = self.plans();
+        plans[index as usize % plans.len()]
+    }
+
+    /// Token accounts of the wallets, plan vaults and treasuries, with the wallet that has to sign
+    /// for their tokens to leave - the plan owner for vaults, nobody for treasuries
+    fn token_accounts(&self) -> Vec<(Pubkey, Option<Pubkey>)> {
+        let mut token_accounts = vec![];
+        for mint in &self.mints {
+            for wallet in &self.wallets {
+                token_accounts.push((
+                    pda::token_account(&wallet.pubkey(), mint),
+                    Some(wallet.pubkey()),
+                ));
+            }
+            for (plan, owner) in self.plans() {
+                token_accounts.push((pda::vault(&plan, mint), Some(owner)));
+            }
+            token_accounts.push((pda::treasury(mint), None));
+        }
+        token_accounts
+    }
+
+    fn token_account(&self, index: u8) -> Pubkey {
+        let token_accounts = self.token_accounts();
+        token_accounts[index as usize % token_accounts.len()].0
+    }
+
+    fn balances(&self) -> HashMap<Pubkey, (Pubkey, u64)> {
+        self.token_accounts()
+            .into_iter()
+            .filter_map(|(address, _)| {
+                let account = self.svm.get_account(&address)?;
+                let token_account = TokenAccount::unpack(&account.data).ok()?;
+                Some((address, (token_account.mint, token_account.amount)))
+            })
+            .collect()
+    }
+
+    fn instruction(&self, instruction: &FuzzInstruction) -> Option<Instruction> {
+        let instruction = match *instruction {
+            FuzzInstruction::Initialize {
+                owner,
+                owner_ata,
+                mint,
+                id,
+                number_of_days,
+                daily_frequency,
+                duration_minutes,
+                commitment_stake,
+                beneficiary,
+                start_at,
+            } => instructions::initialize(
+                self.wallet(owner).pubkey(),
+                self.token_account(owner_ata),
+                self.mint(mint),
+                u64::from(id) % PLAN_IDS,
+                PlanParams {
+                    number_of_days,
+                    daily_frequency,
+                    duration_minutes,
+                    commitment_stake,
+                },
+                beneficiary.map(|index| self.wallet(index).pubkey()),
+                None,
+                start_at,
+            ),
+            FuzzInstruction::Attest {
+                attester,
+                plan,
+                started_at,
+                ended_at,
+            } => {
+                let (plan, owner) = self.plan(plan);
+                instructions::attest(
+                    self.wallet(attester).pubkey(),
+                    owner,
+                    plan,
+                    started_at,
+                    ended_at,
+                )
+            }
+            FuzzInstruction::Complete {
+                owner,
+                plan,
+                mint,
+                beneficiary,
+                beneficiary_ata,
+            } => instructions::complete(
+                self.wallet(owner).pubkey(),
+                self.mint(mint),
+                self.plan(plan).0,
+                self.wallet(beneficiary).pubkey(),
+                self.token_account(beneficiary_ata),
+                None,
+            ),
+            FuzzInstruction::RawData {
+                kind,
+                signer,
+                plan,
+                ref data,
+            } => {
+                let signer = self.wallet(signer).pubkey();
+                let (plan, owner) = self.plan(plan);
+                let mut instruction = match kind % 3 {
+                    0 => instructions::initialize(
+                        signer,
+                        pda::token_account(&signer, &USDC_MINT),
+                        USDC_MINT,
+                        0,
+                        PlanParams {
+                            number_of_days: 7,
+                            daily_frequency: 1,
+                            duration_minutes: 5,
+                            commitment_stake: 0,
+                        },
+                        None,
+                        None,
+                        None,
+                    ),
+                    1 => instructions::attest(signer, owner, plan, 0, 0),
+                    _ => instructions::complete(
+                        signer,
+                        USDC_MINT,
+                        plan,
+                        owner,
+                        pda::token_account(&owner, &USDC_MINT),
+                        None,
+                    ),
+                };
+                instruction.data.truncate(8);
+                instruction.data.extend_from_slice(data);
+                instruction
+            }
+            FuzzInstruction::Warp { .. } => return None,
+        };
+        Some(instruction)
+    }
+}
+
+fn token_account_data(mint: Pubkey, owner: Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0; TokenAccount::LEN];
+    TokenAccount {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    }
+    .pack_into_slice(&mut data);
+    data
+}
+
+fn set_token_program_account(svm: &mut LiteSVM, address: Pubkey, data: Vec<u8>) {
+    svm.set_account(
+        address,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+/// A program with USDC and a second mint, two funded wallets and a config charging fees, so
+/// settlements move tokens to the treasury as well
+fn base_harness() -> Harness {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(capstone::ID, PROGRAM_PATH)
+        .expect("Run `anchor build` before fuzzing the program");
+
+    let wallets = vec![Keypair::new(), Keypair::new()];
+    let mints = vec![USDC_MINT, Pubkey::new_unique()];
+    for mint in &mints {
+        let mut data = vec![0; Mint::LEN];
+        Mint {
+            mint_authority: COption::None,
+            supply: WALLET_TOKENS * wallets.len() as u64,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        }
+        .pack_into_slice(&mut data);
+        set_token_program_account(&mut svm, *mint, data);
+
+        for wallet in &wallets {
+            set_token_program_account(
+                &mut svm,
+                pda::token_account(&wallet.pubkey(), mint),
+                token_account_data(*mint, wallet.pubkey(), WALLET_TOKENS),
+            );
+        }
+    }
+    for wallet in &wallets {
+        svm.airdrop(&wallet.pubkey(), 100_000_000_000).unwrap();
+    }
+
+    let (config, bump) = pda::config();
+    let mut data = vec![];
+    Config {
+        admin: wallets[0].pubkey(),
+        attestation_oracle: wallets[1].pubkey(),
+        bump,
+        lending_program: Pubkey::default(),
+        owner_yield_bps: 10_000,
+        penalty_fee_bps: 1_000,
+        yield_fee_bps: 0,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    debug_assert!(data.starts_with(Config::DISCRIMINATOR));
+    svm.set_account(
+        config,
+        Account {
+            lamports: 1_000_000_000,
+            data,
+            owner: capstone::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = START_TIME;
+    svm.set_sysvar::<Clock>(&clock);
+
+    Harness {
+        svm,
+        wallets,
+        mints,
+    }
+}
+
+thread_local! {
+    static BASE: Harness = base_harness();
+}
+
+fn fresh_harness() -> Harness {
+    BASE.with(|base| Harness {
+        svm: base.svm.clone(),
+        wallets: base
+            .wallets
+            .iter()
+            .map(|wallet| wallet.insecure_clone())
+            .collect(),
+        mints: base.mints.clone(),
+    })
+}
+
+fuzz_target!(|steps: Vec<Step>| {
+    let mut harness = fresh_harness();
+
+    for step in steps {
+        if let FuzzInstruction::Warp { seconds } = step.instruction {
+            let mut clock = harness.svm.get_sysvar::<Clock>();
+            clock.unix_timestamp += i64::from(seconds);
+            harness.svm.set_sysvar::<Clock>(&clock);
+            continue;
+        }
+        let Some(mut instruction) = harness.instruction(&step.instruction) else {
+            continue;
+        };
+        if let Some((a, b)) = step.swap {
+            let count = instruction.accounts.len();
+            let (a, b) = (a as usize % count, b as usize % count);
+            let key = instruction.accounts[a].pubkey;
+            instruction.accounts[a].pubkey = instruction.accounts[b].pubkey;
+            instruction.accounts[b].pubkey = key;
+        }
+
+        // Only wallets the harness holds keys for can sign
+        let signers: Vec<&Keypair> = instruction
+            .accounts
+            .iter()
+            .filter(|meta| meta.is_signer)
+            .map(|meta| {
+                harness
+                    .wallets
+                    .iter()
+                    .find(|wallet| wallet.pubkey() == meta.pubkey)
+            })
+            .collect::<Option<_>>()
+            .unwrap_or_default();
+        let Some(payer) = signers.first() else {
+            continue;
+        };
+        let mut transaction =
+            Transaction::new_with_payer(&[instruction.clone()], Some(&payer.pubkey()));
+        if transaction
+            .try_sign(&signers, harness.svm.latest_blockhash())
+            .is_err()
+        {
+            continue;
+        }
+
+        let before = harness.balances();
+        let result = harness.svm.send_transaction(transaction);
+        harness.svm.expire_blockhash();
+
+        let logs = match &result {
+            Ok(metadata) => &metadata.logs,
+            Err(failure) => &failure.meta.logs,
+        };
+        assert!(
+            !logs.iter().any(|log| log.contains("panicked")),
+            "The program panicked on {step:?}: {logs:#?}"
+        );
+        if result.is_err() {
+            continue;
+        }
+
+        let signed: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+        let after = harness.balances();
+        let mut supply_change: HashMap<Pubkey, i128> = HashMap::new();
+        for (address, authority) in harness.token_accounts() {
+            let (mint, before) = before.get(&address).copied().unwrap_or_default();
+            let (mint_after, after) = after.get(&address).copied().unwrap_or_default();
+            let mint = if before == 0 { mint_after } else { mint };
+            *supply_change.entry(mint).or_default() += i128::from(after) - i128::from(before);
+
+            if after < before {
+                let authority = authority
+                    .unwrap_or_else(|| panic!("Tokens left treasury {address} on {step:?}"));
+                assert!(
+                    signed.contains(&authority),
+                    "Tokens left {address} without its owner signing on {step:?}"
+                );
+            }
+        }
+        for (mint, change) in supply_change {
+            assert_eq!(
+                change, 0,
+                "Tokens of {mint} left the known accounts on {step:?}"
+            );
+        }
+
+        for (plan, owner) in harness.plans() {
+            let wrong_mint_vault = pda::vault(&plan, &harness.mints[1]);
+            assert_eq!(
+                after
+                    .get(&wrong_mint_vault)
+                    .map_or(0, |(_, amount)| *amount),
+                0,
+                "A plan took a stake in the wrong mint on {step:?}"
+            );
+
+            let Some(account) = harness.svm.get_account(&plan) else {
+                continue;
+            };
+            let plan = accounts::meditation_plan(&account.data).expect("Plans should decode");
+            assert_eq!(plan.owner, owner);
+            assert!(plan.rewards <= plan.commitment_stake);
+            if plan.status != PlanStatus::Completed {
+                assert!(plan.attestations.len() as u64 <= plan.total_sessions());
+            }
+        }
+    }
+});
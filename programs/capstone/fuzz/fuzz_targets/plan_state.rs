@@ -0,0 +1,164 @@
+//! Drives `MeditationPlan` through arbitrary owner actions with arbitrary instruction arguments and
+//! a clock that only moves forward, checking that nothing panics and the stake accounting holds.
+
+#![no_main]
+
+use arbitrary::Arbitrary;
+use capstone::MeditationPlan;
+use libfuzzer_sys::fuzz_target;
+use solana_pubkey::Pubkey;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    number_of_days: u8,
+    daily_frequency: u8,
+    duration_minutes: u8,
+    commitment_stake: u64,
+    penalty_bps: u16,
+    mint_decimals: u8,
+    start_at: u32,
+    /// How long before `start_at` the plan is created - it is scheduled until then
+    start_delay: u32,
+    actions: Vec<Action>,
+}
+
+/// An instruction against the plan, `elapsed` seconds after the previous one
+#[derive(Arbitrary, Debug)]
+enum Action {
+    Attest {
+        elapsed: u32,
+        by_owner: bool,
+        started_at: i64,
+        ended_at: i64,
+    },
+    SubmitLateAttestation {
+        elapsed: u32,
+        started_at: i64,
+        ended_at: i64,
+    },
+    Complete {
+        elapsed: u32,
+    },
+    IncreaseStake {
+        elapsed: u32,
+        amount: u64,
+    },
+    ExtendPlan {
+        elapsed: u32,
+        additional_days: u8,
+    },
+    PausePlan {
+        elapsed: u32,
+    },
+    ResumePlan {
+        elapsed: u32,
+    },
+    CancelPlan {
+        elapsed: u32,
+    },
+    ClosePlan {
+        elapsed: u32,
+    },
+}
+
+impl Action {
+    fn elapsed(&self) -> u32 {
+        match self {
+            Action::Attest { elapsed, .. }
+            | Action::SubmitLateAttestation { elapsed, .. }
+            | Action::Complete { elapsed }
+            | Action::IncreaseStake { elapsed, .. }
+            | Action::ExtendPlan { elapsed, .. }
+            | Action::PausePlan { elapsed }
+            | Action::ResumePlan { elapsed }
+            | Action::CancelPlan { elapsed }
+            | Action::ClosePlan { elapsed } => *elapsed,
+        }
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let parameters = MeditationPlan::validate_parameters(
+        input.number_of_days,
+        input.daily_frequency,
+        input.duration_minutes,
+        input.commitment_stake,
+        input.mint_decimals,
+    );
+    if parameters.is_err() {
+        return;
+    }
+
+    let owner = Pubkey::new_unique();
+    let mut plan = MeditationPlan::new(
+        owner,
+        owner,
+        1,
+        255,
+        input.number_of_days,
+        input.daily_frequency,
+        input.duration_minutes,
+        input.commitment_stake,
+        input.start_at.into(),
+        Pubkey::default(),
+    );
+    plan.penalty_bps = input.penalty_bps % (MeditationPlan::FULL_PENALTY_BPS + 1);
+
+    let mut now = plan.start_at - i64::from(input.start_delay);
+    if plan.schedule(now).is_err() {
+        return;
+    }
+    for action in input.actions {
+        now += i64::from(action.elapsed());
+
+        // A failed instruction reverts its transaction, so only successful ones are kept
+        let mut next = plan.clone();
+        let result = match action {
+            Action::Attest {
+                by_owner,
+                started_at,
+                ended_at,
+                ..
+            } => {
+                let attester = if by_owner {
+                    owner
+                } else {
+                    Pubkey::new_unique()
+                };
+                next.save_attestation(attester, started_at, ended_at, now)
+            }
+            Action::SubmitLateAttestation {
+                started_at,
+                ended_at,
+                ..
+            } => next.save_late_attestation(Pubkey::new_unique(), started_at, ended_at, now),
+            Action::Complete { .. } => next.complete(now),
+            Action::IncreaseStake { amount, .. } => {
+                next.increase_stake(amount, input.mint_decimals, now)
+            }
+            Action::ExtendPlan {
+                additional_days, ..
+            } => next.extend(additional_days, now),
+            Action::PausePlan { .. } => next.pause(now),
+            Action::ResumePlan { .. } => next.resume(now),
+            Action::CancelPlan { .. } => next.cancel(now),
+            Action::ClosePlan { .. } => next.retire(now),
+        };
+        if result.is_ok() {
+            plan = next;
+        }
+
+        assert!(plan.rewards <= plan.commitment_stake);
+        assert!(plan.sessions_completed() <= plan.total_sessions());
+        let settlement = plan
+            .projected_settlement(now)
+            .expect("Projecting the settlement should never fail");
+        assert_eq!(
+            settlement.rewards + settlement.penalties,
+            plan.commitment_stake
+        );
+        plan.status_at(now);
+        plan.settlement_deadline();
+        plan.completion_ratio_bps();
+    }
+});
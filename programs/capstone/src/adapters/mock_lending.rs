@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use mock_lending::cpi::accounts::{Deposit, Withdraw};
+
+use crate::adapters::{LendingAdapter, LendingContext};
+use crate::error::MeditationPlanError;
+
+/// Adapter for the workspace `mock-lending` program.
+///
+/// Remaining accounts: `[lending_program, pool, position, reserve]`
+pub struct MockLendingAdapter<'a, 'info> {
+    ctx: LendingContext<'a, 'info>,
+    program: AccountInfo<'info>,
+    pool: AccountInfo<'info>,
+    position: AccountInfo<'info>,
+    reserve: AccountInfo<'info>,
+}
+
+impl<'a, 'info> MockLendingAdapter<'a, 'info> {
+    pub fn try_new(ctx: LendingContext<'a, 'info>) -> Result<Self> {
+        let [program, pool, position, reserve, ..] = ctx.remaining_accounts else {
+            return err!(MeditationPlanError::InvalidLendingProgram);
+        };
+
+        Ok(Self {
+            program: program.clone(),
+            pool: pool.clone(),
+            position: position.clone(),
+            reserve: reserve.clone(),
+            ctx,
+        })
+    }
+}
+
+impl LendingAdapter for MockLendingAdapter<'_, '_> {
+    fn deposit(&self, amount: u64, plan_signer: &[&[&[u8]]]) -> Result<()> {
+        let cpi_accounts = Deposit {
+            payer: self.ctx.payer.clone(),
+            depositor: self.ctx.meditation_plan.clone(),
+            pool: self.pool.clone(),
+            position: self.position.clone(),
+            mint: self.ctx.mint.clone(),
+            depositor_token_account: self.ctx.vault.clone(),
+            reserve: self.reserve.clone(),
+            token_program: self.ctx.token_program.clone(),
+            system_program: self.ctx.system_program.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(self.program.clone(), cpi_accounts, plan_signer);
+
+        mock_lending::cpi::deposit(cpi_ctx, amount)
+    }
+
+    fn withdraw(&self, plan_signer: &[&[&[u8]]]) -> Result<()> {
+        let cpi_accounts = Withdraw {
+            depositor: self.ctx.meditation_plan.clone(),
+            pool: self.pool.clone(),
+            position: self.position.clone(),
+            mint: self.ctx.mint.clone(),
+            depositor_token_account: self.ctx.vault.clone(),
+            reserve: self.reserve.clone(),
+            token_program: self.ctx.token_program.clone(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(self.program.clone(), cpi_accounts, plan_signer);
+
+        mock_lending::cpi::withdraw(cpi_ctx)
+    }
+}
@@ -0,0 +1,50 @@
+pub mod mock_lending;
+
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+
+pub use mock_lending::*;
+
+/// Accounts from the calling instruction that every lending adapter needs.
+///
+/// Protocol specific accounts are passed as remaining accounts, starting with the lending program
+/// itself, so new adapters can be added without changing the instruction account structs.
+pub struct LendingContext<'a, 'info> {
+    pub payer: AccountInfo<'info>,
+    pub meditation_plan: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub vault: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+    pub remaining_accounts: &'a [AccountInfo<'info>],
+}
+
+/// Moves the plan vault's tokens in and out of a lending program - the plan PDA signs for the vault
+pub trait LendingAdapter {
+    fn deposit(&self, amount: u64, plan_signer: &[&[&[u8]]]) -> Result<()>;
+
+    /// Withdraws the full position, principal plus accrued yield, back into the vault
+    fn withdraw(&self, plan_signer: &[&[&[u8]]]) -> Result<()>;
+}
+
+pub fn lending_adapter<'a, 'info: 'a>(
+    lending_program: Pubkey,
+    ctx: LendingContext<'a, 'info>,
+) -> Result<Box<dyn LendingAdapter + 'a>> {
+    let program = ctx
+        .remaining_accounts
+        .first()
+        .ok_or(MeditationPlanError::InvalidLendingProgram)?;
+    require_keys_eq!(
+        program.key(),
+        lending_program,
+        MeditationPlanError::InvalidLendingProgram
+    );
+
+    if lending_program == ::mock_lending::ID {
+        return Ok(Box::new(MockLendingAdapter::try_new(ctx)?));
+    }
+
+    err!(MeditationPlanError::InvalidLendingProgram)
+}
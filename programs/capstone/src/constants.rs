@@ -5,6 +5,10 @@ pub const ANCHOR_DISCRIMINATOR: u8 = 8;
 
 pub const HOUR_IN_SECONDS: i64 = 60 * 60; // 1 hour in seconds
 pub const DAY_IN_SECONDS: i64 = 24 * HOUR_IN_SECONDS; // 24 hours in seconds
+pub const MAX_PAUSE_SECONDS: i64 = 14 * DAY_IN_SECONDS; // Total time a plan can be paused
+pub const SETTLEMENT_GRACE_SECONDS: i64 = 3 * DAY_IN_SECONDS; // Owner-only window after a plan ends
+pub const DISPUTE_WINDOW_SECONDS: i64 = 24 * HOUR_IN_SECONDS; // Late attestations window after a plan ends
+pub const MAX_SCHEDULE_SECONDS: i64 = 30 * DAY_IN_SECONDS; // How far ahead a plan can be scheduled to start
 
 // Choose USDC mint based on feature flags
 #[constant]
@@ -19,3 +23,8 @@ pub const USDC_MINT: Pubkey = pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDn
 #[constant]
 #[cfg(not(any(feature = "mainnet", feature = "devnet")))]
 pub const USDC_MINT: Pubkey = pubkey!("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU");
+
+// Soul-bound completion badge metadata - plan details are stored as additional metadata fields
+pub const BADGE_NAME: &str = "SOL Journey Meditation Badge";
+pub const BADGE_SYMBOL: &str = "SOLJ";
+pub const BADGE_URI: &str = "";
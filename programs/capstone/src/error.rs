@@ -32,4 +32,53 @@ pub enum MeditationPlanError {
     PlanNotStarted,
     #[msg("Unauthorized access to the meditation plan")]
     UnauthorizedAccess,
+    // Error codes are part of the program interface - add new variants at the end
+    #[msg("Completion badge has already been minted for this plan")]
+    BadgeAlreadyMinted,
+    #[msg("Meditation plan has not been completed yet")]
+    PlanNotCompleted,
+    #[msg("Basis points must be between 0 and 10000")]
+    InvalidBasisPoints,
+    #[msg("Lending program is not approved or its accounts are missing")]
+    InvalidLendingProgram,
+    #[msg("Only the protocol admin can perform this action")]
+    UnauthorizedAdmin,
+    #[msg("Meditation plan has used up its pause allowance")]
+    PauseLimitExceeded,
+    #[msg("Meditation plan is not paused")]
+    PlanNotPaused,
+    #[msg("All planned sessions have already been attested")]
+    NoRemainingSessions,
+    #[msg("Expired plans can only be settled by others after the grace period")]
+    GracePeriodActive,
+    #[msg("Beneficiary does not match the meditation plan")]
+    InvalidBeneficiary,
+    #[msg("Template name must be between 1 and 32 bytes")]
+    InvalidTemplateName,
+    #[msg("Withdrawal amount must be greater than zero and covered by the treasury")]
+    InvalidWithdrawalAmount,
+    #[msg("Charity is not approved or does not match the meditation plan")]
+    InvalidCharity,
+    #[msg("Charity name must be between 1 and 32 bytes")]
+    InvalidCharityName,
+    #[msg("Penalties can only be settled once the dispute window has closed")]
+    DisputeWindowActive,
+    #[msg("Late attestations are only accepted during the dispute window after the plan ends")]
+    DisputeWindowClosed,
+    #[msg("Late attestations must be co-signed by the attestation oracle")]
+    UnauthorizedOracle,
+    #[msg("Meditation plan account does not match any known layout")]
+    InvalidPlanLayout,
+    #[msg("Meditation plan already uses the current layout")]
+    PlanAlreadyMigrated,
+    #[msg("Meditation plan must be migrated to the current layout")]
+    PlanNotMigrated,
+    #[msg("Template penalty must be at least 25% of the value of missed sessions")]
+    TemplatePenaltyTooLow,
+    #[msg("Completion badge is only minted for plans with every session attested")]
+    BadgeNotEarned,
+    #[msg("Meditation plan cannot move to the requested status")]
+    InvalidStatusTransition,
+    #[msg("Meditation plan must start now or within the next 30 days")]
+    InvalidStartTime,
 }
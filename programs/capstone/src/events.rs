@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+use crate::state::CharityKind;
+
+/// Emitted when a settled plan's penalties are donated to its charity or anti-charity
+#[event]
+pub struct CharityDonation {
+    pub amount: u64,
+    pub charity: Pubkey,
+    pub kind: CharityKind,
+    pub meditation_plan: Pubkey,
+    /// The charity's tally including this donation
+    pub total_donated: u64,
+}
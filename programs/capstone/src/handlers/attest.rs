@@ -1,7 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::error::MeditationPlanError;
-use crate::state::MeditationPlan;
+use crate::state::{MeditationPlan, Practitioner};
 
 #[derive(Accounts)]
 pub struct Attest<'info> {
@@ -16,17 +15,29 @@ pub struct Attest<'info> {
             meditation_plan.id.to_le_bytes().as_ref()
         ],
         bump = meditation_plan.bump,
-        constraint = meditation_plan.is_active @ MeditationPlanError::PlanInactive,
-        constraint = !meditation_plan.is_completed @ MeditationPlanError::PlanCompleted,
     )]
     pub meditation_plan: Account<'info, MeditationPlan>,
 
+    #[account(
+        init_if_needed,
+        payer = attester,
+        space = Practitioner::DISCRIMINATOR.len() + Practitioner::INIT_SPACE,
+        seeds = [b"practitioner", meditation_plan.owner.key().as_ref()],
+        bump,
+    )]
+    pub practitioner: Account<'info, Practitioner>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Attest<'info> {
-    pub fn attest(&mut self, started_at: i64, ended_at: i64) -> Result<()> {
+    pub fn attest(&mut self, started_at: i64, ended_at: i64, bumps: &AttestBumps) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
         self.meditation_plan
-            .save_attestation(self.attester.key(), started_at, ended_at)
+            .save_attestation(self.attester.key(), started_at, ended_at, now)?;
+
+        self.practitioner
+            .initialize_if_needed(self.meditation_plan.owner, bumps.practitioner);
+        self.practitioner.record_session(started_at, ended_at)
     }
 }
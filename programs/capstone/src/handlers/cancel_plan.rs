@@ -0,0 +1,140 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::adapters::{lending_adapter, LendingContext};
+use crate::constants::USDC_MINT;
+use crate::error::MeditationPlanError;
+use crate::state::MeditationPlan;
+
+#[derive(Accounts)]
+pub struct CancelPlan<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        has_one = owner @ MeditationPlanError::UnauthorizedAccess,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = meditation_plan,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelPlan<'info> {
+    /// Cancels a plan that hasn't started yet, refunding the whole vault and the rent reserve to
+    /// the owner. Lending plans pass the adapter's accounts to withdraw the stake first.
+    pub fn cancel_plan(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.meditation_plan.cancel(now)?;
+
+        if self.meditation_plan.is_lending() {
+            self.withdraw_from_lending(remaining_accounts)?;
+        }
+
+        self.refund(self.vault.amount)?;
+        self.release_rent_reserve()
+    }
+
+    fn refund(&self, amount: u64) -> Result<()> {
+        if amount < 1 {
+            return Ok(());
+        }
+
+        let owner_key = self.owner.key();
+        let id_bytes = self.meditation_plan.id.to_le_bytes();
+        let seeds = &[
+            b"meditation_plan",
+            owner_key.as_ref(),
+            id_bytes.as_ref(),
+            &[self.meditation_plan.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            to: self.owner_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            authority: self.meditation_plan.to_account_info(),
+        };
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+
+    /// Returns the lamports the plan holds above its own rent to the owner
+    fn release_rent_reserve(&self) -> Result<()> {
+        let plan = self.meditation_plan.to_account_info();
+        let rent_exempt = Rent::get()?.minimum_balance(plan.data_len());
+        let reserve = plan.lamports().saturating_sub(rent_exempt);
+        if reserve < 1 {
+            return Ok(());
+        }
+
+        plan.sub_lamports(reserve)?;
+        self.owner.add_lamports(reserve)?;
+        Ok(())
+    }
+
+    /// Pulls the position back into the vault, yield included
+    fn withdraw_from_lending(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let owner_key = self.owner.key();
+        let id_bytes = self.meditation_plan.id.to_le_bytes();
+        let seeds = &[
+            b"meditation_plan",
+            owner_key.as_ref(),
+            id_bytes.as_ref(),
+            &[self.meditation_plan.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let adapter = lending_adapter(
+            self.meditation_plan.lending_program,
+            LendingContext {
+                payer: self.owner.to_account_info(),
+                meditation_plan: self.meditation_plan.to_account_info(),
+                mint: self.mint.to_account_info(),
+                vault: self.vault.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                remaining_accounts,
+            },
+        )?;
+        adapter.withdraw(signer)?;
+        self.vault.reload()
+    }
+}
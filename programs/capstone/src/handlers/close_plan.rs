@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::constants::USDC_MINT;
+use crate::error::MeditationPlanError;
+use crate::state::MeditationPlan;
+
+#[derive(Accounts)]
+pub struct ClosePlan<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        has_one = owner @ MeditationPlanError::UnauthorizedAccess,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = meditation_plan,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClosePlan<'info> {
+    /// Closes a completed or cancelled plan and its vault, returning their rent to the owner.
+    /// Tokens sent to the vault since it was settled go to the owner as well.
+    pub fn close_plan(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.meditation_plan.retire(now)?;
+
+        let owner_key = self.owner.key();
+        let id_bytes = self.meditation_plan.id.to_le_bytes();
+        let seeds = &[
+            b"meditation_plan",
+            owner_key.as_ref(),
+            id_bytes.as_ref(),
+            &[self.meditation_plan.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        if self.vault.amount > 0 {
+            let cpi_accounts = TransferChecked {
+                from: self.vault.to_account_info(),
+                to: self.owner_ata.to_account_info(),
+                mint: self.mint.to_account_info(),
+                authority: self.meditation_plan.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            transfer_checked(cpi_ctx, self.vault.amount, self.mint.decimals)?;
+        }
+
+        let cpi_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.owner.to_account_info(),
+            authority: self.meditation_plan.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer);
+        close_account(cpi_ctx)
+    }
+}
@@ -1,12 +1,14 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
+use crate::adapters::{lending_adapter, LendingContext};
 use crate::constants::USDC_MINT;
 use crate::error::MeditationPlanError;
-use crate::state::MeditationPlan;
+use crate::events::CharityDonation;
+use crate::state::{Charity, Config, MeditationPlan, Practitioner};
 
 #[derive(Accounts)]
 pub struct Complete<'info> {
@@ -21,26 +23,40 @@ pub struct Complete<'info> {
             meditation_plan.id.to_le_bytes().as_ref()
         ],
         bump = meditation_plan.bump,
-        constraint = meditation_plan.is_active @ MeditationPlanError::PlanInactive,
-        constraint = !meditation_plan.is_completed @ MeditationPlanError::PlanCompleted,
         has_one = owner @ MeditationPlanError::UnauthorizedAccess,
     )]
     pub meditation_plan: Account<'info, MeditationPlan>,
 
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Practitioner::DISCRIMINATOR.len() + Practitioner::INIT_SPACE,
+        seeds = [b"practitioner", owner.key().as_ref()],
+        bump,
+    )]
+    pub practitioner: Account<'info, Practitioner>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     #[account(
         mint::token_program = token_program,
         constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
     )]
     pub mint: InterfaceAccount<'info, Mint>,
 
+    /// CHECK: Wallet receiving the payout - checked against the plan
+    #[account(address = meditation_plan.beneficiary @ MeditationPlanError::InvalidBeneficiary)]
+    pub beneficiary: UncheckedAccount<'info>,
+
     #[account(
         init_if_needed,
         payer = owner,
         associated_token::mint = mint,
-        associated_token::authority = owner,
+        associated_token::authority = beneficiary,
         associated_token::token_program = token_program,
     )]
-    pub owner_ata: InterfaceAccount<'info, TokenAccount>,
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -50,20 +66,177 @@ pub struct Complete<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Required when the plan donates its penalties - checked against the plan at settlement
+    #[account(mut)]
+    pub charity: Option<Account<'info, Charity>>,
+
+    /// CHECK: Wallet of the plan's charity - its ATA is checked against the charity at settlement
+    pub charity_wallet: Option<UncheckedAccount<'info>>,
+
+    // Created if the charity closed it since registering, so the settlement can't be blocked
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = charity_wallet,
+        associated_token::token_program = token_program,
+    )]
+    pub charity_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Complete<'info> {
-    pub fn complete(&mut self) -> Result<()> {
-        self.meditation_plan.complete()?;
-        self.transfer_rewards()
+    pub fn complete(
+        &mut self,
+        bumps: &CompleteBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let mut settlement = PlanSettlement {
+            payer: self.owner.to_account_info(),
+            meditation_plan: &mut self.meditation_plan,
+            practitioner: &mut self.practitioner,
+            practitioner_bump: bumps.practitioner,
+            config: &self.config,
+            mint: &self.mint,
+            vault: &mut self.vault,
+            treasury: self.treasury.to_account_info(),
+            charity: self.charity.as_mut(),
+            charity_ata: self.charity_ata.as_ref().map(|ata| ata.to_account_info()),
+            token_program: &self.token_program,
+            system_program: &self.system_program,
+        };
+        let payout = settlement.settle(remaining_accounts)?;
+        settlement.transfer_from_vault(self.beneficiary_ata.to_account_info(), payout)?;
+        settlement.release_rent_reserve(&self.owner.to_account_info())
+    }
+}
+
+/// Accounts needed to settle a finished plan, shared by `complete` and `renew`
+pub struct PlanSettlement<'a, 'info> {
+    /// Pays for any accounts the lending adapter creates
+    pub payer: AccountInfo<'info>,
+    pub meditation_plan: &'a mut Account<'info, MeditationPlan>,
+    pub practitioner: &'a mut Account<'info, Practitioner>,
+    pub practitioner_bump: u8,
+    pub config: &'a Account<'info, Config>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub vault: &'a mut InterfaceAccount<'info, TokenAccount>,
+    /// Collects the protocol fee - owned by the config PDA
+    pub treasury: AccountInfo<'info>,
+    /// Receives the penalties of plans that chose a charity
+    pub charity: Option<&'a mut Account<'info, Charity>>,
+    pub charity_ata: Option<AccountInfo<'info>>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+    pub system_program: &'a Program<'info, System>,
+}
+
+impl<'info> PlanSettlement<'_, 'info> {
+    /// Finalizes rewards and penalties, records the outcome for the practitioner, sends the
+    /// treasury its share and any donation to the charity, and returns the amount owed to the
+    /// owner from the vault - which is all that is left in it.
+    ///
+    /// Penalties donated to a charity are not charged the penalty fee.
+    pub fn settle(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<u64> {
+        let now = Clock::get()?.unix_timestamp;
+        self.meditation_plan.complete(now)?;
+
+        if self.meditation_plan.is_lending() {
+            self.withdraw_from_lending(remaining_accounts)?;
+        }
+
+        self.practitioner
+            .initialize_if_needed(self.meditation_plan.owner, self.practitioner_bump);
+        self.practitioner
+            .record_plan(self.meditation_plan.all_sessions_completed());
+
+        let owner_yield = self
+            .config
+            .owner_yield_share(self.meditation_plan.accrued_yield)?;
+        let (payout, protocol_yield) = self
+            .meditation_plan
+            .settle_vault(self.vault.amount, owner_yield)?;
+
+        let has_charity = self.meditation_plan.has_charity();
+        let charged_penalties = if has_charity {
+            0
+        } else {
+            self.meditation_plan.penalties
+        };
+        self.meditation_plan.protocol_fee = self
+            .config
+            .protocol_fee(charged_penalties, protocol_yield)?;
+        let treasury_share = self.meditation_plan.treasury_share(protocol_yield)?;
+        self.transfer_from_vault(self.treasury.clone(), treasury_share)?;
+
+        if has_charity {
+            self.donate()?;
+        }
+
+        Ok(payout)
     }
 
-    fn transfer_rewards(&mut self) -> Result<()> {
-        let rewards = self.meditation_plan.rewards.min(self.vault.amount);
-        if rewards < 1 {
+    /// Sends the penalties to the plan's charity or anti-charity and adds them to its tally
+    fn donate(&mut self) -> Result<()> {
+        let charity = self
+            .charity
+            .as_deref()
+            .ok_or(MeditationPlanError::InvalidCharity)?;
+        let charity_ata = self
+            .charity_ata
+            .clone()
+            .ok_or(MeditationPlanError::InvalidCharity)?;
+        require_keys_eq!(
+            charity.key(),
+            self.meditation_plan.charity,
+            MeditationPlanError::InvalidCharity
+        );
+        require_keys_eq!(
+            charity_ata.key(),
+            get_associated_token_address_with_program_id(
+                &charity.wallet,
+                &self.mint.key(),
+                &self.token_program.key()
+            ),
+            MeditationPlanError::InvalidCharity
+        );
+
+        let amount = self.meditation_plan.penalties;
+        if amount < 1 {
+            return Ok(());
+        }
+        self.transfer_from_vault(charity_ata, amount)?;
+        self.meditation_plan.donated = amount;
+
+        let charity = self
+            .charity
+            .as_deref_mut()
+            .ok_or(MeditationPlanError::InvalidCharity)?;
+        charity.record_donation(amount)?;
+        emit!(CharityDonation {
+            amount,
+            charity: charity.key(),
+            kind: charity.kind,
+            meditation_plan: self.meditation_plan.key(),
+            total_donated: charity.total_donated,
+        });
+        Ok(())
+    }
+
+    /// Transfers out of the vault, signed by the plan
+    pub fn transfer_from_vault(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if amount < 1 {
             return Ok(());
         }
 
@@ -79,13 +252,72 @@ impl<'info> Complete<'info> {
 
         let cpi_accounts = TransferChecked {
             from: self.vault.to_account_info(),
-            to: self.owner_ata.to_account_info(),
+            to,
             mint: self.mint.to_account_info(),
             authority: self.meditation_plan.to_account_info(),
         };
         let cpi_program = self.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
 
-        transfer_checked(cpi_ctx, rewards, self.mint.decimals)
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+
+    /// Moves the lamports the plan holds above its own rent - the reserve deposited at initialize
+    /// for the accounts a keeper may have to create at settlement - to the given account
+    pub fn release_rent_reserve(&self, to: &AccountInfo<'info>) -> Result<()> {
+        self.reimburse_from_rent_reserve(to, u64::MAX)
+    }
+
+    /// Moves up to `amount` of the rent reserve to the given account
+    pub fn reimburse_from_rent_reserve(&self, to: &AccountInfo<'info>, amount: u64) -> Result<()> {
+        let plan = self.meditation_plan.to_account_info();
+        let rent_exempt = Rent::get()?.minimum_balance(plan.data_len());
+        let reserve = plan.lamports().saturating_sub(rent_exempt).min(amount);
+        if reserve < 1 {
+            return Ok(());
+        }
+
+        plan.sub_lamports(reserve)?;
+        to.add_lamports(reserve)?;
+        Ok(())
+    }
+
+    /// Pulls the position back into the vault and records everything above the stake as yield
+    fn withdraw_from_lending(&mut self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let owner_key = self.meditation_plan.owner.key();
+        let id_bytes = self.meditation_plan.id.to_le_bytes();
+        let seeds = &[
+            b"meditation_plan",
+            owner_key.as_ref(),
+            id_bytes.as_ref(),
+            &[self.meditation_plan.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let balance_before = self.vault.amount;
+        let adapter = lending_adapter(
+            self.meditation_plan.lending_program,
+            LendingContext {
+                payer: self.payer.clone(),
+                meditation_plan: self.meditation_plan.to_account_info(),
+                mint: self.mint.to_account_info(),
+                vault: self.vault.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                remaining_accounts,
+            },
+        )?;
+        adapter.withdraw(signer)?;
+        self.vault.reload()?;
+
+        let withdrawn = self
+            .vault
+            .amount
+            .checked_sub(balance_before)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.meditation_plan.accrued_yield =
+            withdrawn.saturating_sub(self.meditation_plan.commitment_stake);
+
+        Ok(())
     }
 }
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::constants::USDC_MINT;
+use crate::error::MeditationPlanError;
+use crate::state::PlanTemplate;
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreatePlanTemplate<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = PlanTemplate::DISCRIMINATOR.len() + PlanTemplate::INIT_SPACE,
+        seeds = [b"plan_template", creator.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub plan_template: Account<'info, PlanTemplate>,
+
+    #[account(constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreatePlanTemplate<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_plan_template(
+        &mut self,
+        id: u64,
+        name: String,
+        number_of_days: u8,
+        daily_frequency: u8,
+        duration_minutes: u8,
+        suggested_stake: u64,
+        penalty_bps: u16,
+        bumps: &CreatePlanTemplateBumps,
+    ) -> Result<()> {
+        self.plan_template.set_inner(PlanTemplate {
+            bump: bumps.plan_template,
+            creator: self.creator.key(),
+            daily_frequency,
+            duration_minutes,
+            id,
+            name,
+            number_of_days,
+            penalty_bps,
+            suggested_stake,
+        });
+        self.plan_template.validate(self.mint.decimals)
+    }
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::adapters::{lending_adapter, LendingContext};
+use crate::constants::USDC_MINT;
+use crate::error::MeditationPlanError;
+use crate::state::MeditationPlan;
+
+#[derive(Accounts)]
+pub struct IncreaseStake<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        has_one = owner @ MeditationPlanError::UnauthorizedAccess,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = meditation_plan,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> IncreaseStake<'info> {
+    pub fn increase_stake(
+        &mut self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.meditation_plan
+            .increase_stake(amount, self.mint.decimals, now)?;
+        self.deposit(amount)?;
+
+        if self.meditation_plan.is_lending() {
+            self.deposit_to_lending(amount, remaining_accounts)?;
+        }
+
+        Ok(())
+    }
+
+    fn deposit(&self, amount: u64) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            from: self.owner_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+
+    fn deposit_to_lending(
+        &self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let owner_key = self.owner.key();
+        let id_bytes = self.meditation_plan.id.to_le_bytes();
+        let seeds = &[
+            b"meditation_plan",
+            owner_key.as_ref(),
+            id_bytes.as_ref(),
+            &[self.meditation_plan.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let adapter = lending_adapter(
+            self.meditation_plan.lending_program,
+            LendingContext {
+                payer: self.owner.to_account_info(),
+                meditation_plan: self.meditation_plan.to_account_info(),
+                mint: self.mint.to_account_info(),
+                vault: self.vault.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                remaining_accounts,
+            },
+        )?;
+        adapter.deposit(amount, signer)
+    }
+}
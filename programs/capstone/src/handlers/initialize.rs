@@ -1,13 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::constants::DAY_IN_SECONDS;
+use crate::adapters::{lending_adapter, LendingContext};
 use crate::constants::USDC_MINT;
 use crate::error::MeditationPlanError;
-use crate::state::MeditationPlan;
+use crate::state::{Charity, Config, MeditationPlan};
 
 #[derive(Accounts)]
 #[instruction(id: u64)]
@@ -24,6 +25,13 @@ pub struct Initialize<'info> {
     )]
     pub meditation_plan: Account<'info, MeditationPlan>,
 
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// Optional charity receiving the penalties at settlement
+    #[account(constraint = charity.is_active @ MeditationPlanError::InvalidCharity)]
+    pub charity: Option<Account<'info, Charity>>,
+
     #[account(
         mint::token_program = token_program,
         constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
@@ -53,6 +61,9 @@ pub struct Initialize<'info> {
 }
 
 impl<'info> Initialize<'info> {
+    /// Creates the plan and moves the stake into its vault. A plan with a later `start_at` stays
+    /// `Scheduled` until then and can be cancelled in the meantime.
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize(
         &mut self,
         id: u64,
@@ -60,89 +71,53 @@ impl<'info> Initialize<'info> {
         daily_frequency: u8,
         duration_minutes: u8,
         commitment_stake: u64,
+        beneficiary: Option<Pubkey>,
+        start_at: Option<i64>,
         bumps: &InitializeBumps,
+        remaining_accounts: &[AccountInfo<'info>],
     ) -> Result<()> {
-        self.validate_input(
+        MeditationPlan::validate_parameters(
             number_of_days,
             daily_frequency,
             duration_minutes,
             commitment_stake,
+            self.mint.decimals,
         )?;
 
-        let start_at = Clock::get()?.unix_timestamp;
-        let end_at = start_at + (number_of_days as i64 * DAY_IN_SECONDS);
-        self.meditation_plan.set_inner(MeditationPlan {
-            attestations: vec![],
-            bump: bumps.meditation_plan,
-            commitment_stake,
-            daily_frequency,
-            duration_minutes,
-            end_at,
+        let now = Clock::get()?.unix_timestamp;
+        // Lending accounts are optional - passing them opts the plan into a yield-bearing vault
+        let lending_program = if remaining_accounts.is_empty() {
+            Pubkey::default()
+        } else {
+            require!(
+                self.config.is_lending_enabled(),
+                MeditationPlanError::InvalidLendingProgram
+            );
+            self.config.lending_program
+        };
+
+        self.meditation_plan.set_inner(MeditationPlan::new(
+            self.owner.key(),
+            beneficiary.unwrap_or(self.owner.key()),
             id,
-            is_active: true,
-            is_completed: false,
+            bumps.meditation_plan,
             number_of_days,
-            owner: self.owner.key(),
-            penalties: 0,
-            rewards: 0,
-            start_at,
-        });
-        self.deposit(commitment_stake)
-    }
-
-    fn validate_input(
-        &self,
-        number_of_days: u8,
-        daily_frequency: u8,
-        duration_minutes: u8,
-        commitment_stake: u64,
-    ) -> Result<()> {
-        require_gte!(number_of_days, 7, MeditationPlanError::InvalidNumberOfDays);
-        require_gte!(30, number_of_days, MeditationPlanError::InvalidNumberOfDays);
-
-        require_gte!(
             daily_frequency,
-            1,
-            MeditationPlanError::InvalidDailyFrequency
-        );
-        require_gte!(
-            4,
-            daily_frequency,
-            MeditationPlanError::InvalidDailyFrequency
-        );
-
-        require_gte!(
             duration_minutes,
-            5,
-            MeditationPlanError::InvalidDurationMinutes
-        );
-        require_gte!(
-            60,
-            duration_minutes,
-            MeditationPlanError::InvalidDurationMinutes
-        );
-
-        let decimals = 10u64
-            .checked_pow(self.mint.decimals.into())
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-
-        let min_stake = (10u64)
-            .checked_mul(decimals)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-        require_gte!(
-            commitment_stake,
-            min_stake,
-            MeditationPlanError::InvalidCommitmentStakeAmount
-        );
-
-        let max_stake = (500u64)
-            .checked_mul(decimals)
-            .ok_or(ProgramError::ArithmeticOverflow)?;
-        require_gte!(
-            max_stake,
             commitment_stake,
-            MeditationPlanError::InvalidCommitmentStakeAmount
-        );
+            start_at.unwrap_or(now),
+            lending_program,
+        ));
+        self.meditation_plan.schedule(now)?;
+        if let Some(charity) = &self.charity {
+            self.meditation_plan.charity = charity.key();
+        }
+        self.deposit(commitment_stake)?;
+        self.deposit_rent_reserve()?;
+
+        if self.meditation_plan.is_lending() {
+            self.deposit_to_lending(commitment_stake, remaining_accounts)?;
+        }
 
         Ok(())
     }
@@ -159,4 +134,46 @@ impl<'info> Initialize<'info> {
 
         transfer_checked(cpi_ctx, commitment_stake, self.mint.decimals)
     }
+
+    /// Parks enough lamports on the plan to create every account a settlement may need, so a
+    /// keeper settling an expired plan is reimbursed - what it doesn't use goes back to the owner
+    fn deposit_rent_reserve(&self) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: self.owner.to_account_info(),
+            to: self.meditation_plan.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), cpi_accounts);
+
+        transfer(cpi_ctx, MeditationPlan::rent_reserve()?)
+    }
+
+    fn deposit_to_lending(
+        &self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let owner_key = self.owner.key();
+        let id_bytes = self.meditation_plan.id.to_le_bytes();
+        let seeds = &[
+            b"meditation_plan",
+            owner_key.as_ref(),
+            id_bytes.as_ref(),
+            &[self.meditation_plan.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let adapter = lending_adapter(
+            self.meditation_plan.lending_program,
+            LendingContext {
+                payer: self.owner.to_account_info(),
+                meditation_plan: self.meditation_plan.to_account_info(),
+                mint: self.mint.to_account_info(),
+                vault: self.vault.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                remaining_accounts,
+            },
+        )?;
+        adapter.deposit(amount, signer)
+    }
 }
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+use crate::program::Capstone;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Config::DISCRIMINATOR.len() + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // Only the upgrade authority can create the config, so it can't be front-run after deployment
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key())
+            @ MeditationPlanError::UnauthorizedAdmin,
+    )]
+    pub program: Program<'info, Capstone>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ MeditationPlanError::UnauthorizedAdmin,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize_config(
+        &mut self,
+        lending_program: Pubkey,
+        owner_yield_bps: u16,
+        penalty_fee_bps: u16,
+        yield_fee_bps: u16,
+        attestation_oracle: Pubkey,
+        bumps: &InitializeConfigBumps,
+    ) -> Result<()> {
+        self.config.set_inner(Config {
+            admin: self.admin.key(),
+            attestation_oracle,
+            bump: bumps.config,
+            lending_program,
+            owner_yield_bps,
+            penalty_fee_bps,
+            yield_fee_bps,
+        });
+        self.config.validate()
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::handlers::initialize::*;
+use crate::state::PlanTemplate;
+
+#[derive(Accounts)]
+pub struct InitializeFromTemplate<'info> {
+    #[account(
+        seeds = [
+            b"plan_template",
+            plan_template.creator.key().as_ref(),
+            plan_template.id.to_le_bytes().as_ref()
+        ],
+        bump = plan_template.bump,
+    )]
+    pub plan_template: Account<'info, PlanTemplate>,
+
+    pub initialize: Initialize<'info>,
+}
+
+impl<'info> InitializeFromTemplate<'info> {
+    /// Creates a plan with the template's parameters and penalty rules - the stake defaults to the
+    /// template's suggested stake and is validated like any other plan
+    pub fn initialize_from_template(
+        &mut self,
+        id: u64,
+        commitment_stake: Option<u64>,
+        beneficiary: Option<Pubkey>,
+        bumps: &InitializeFromTemplateBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let template = &self.plan_template;
+        self.initialize.initialize(
+            id,
+            template.number_of_days,
+            template.daily_frequency,
+            template.duration_minutes,
+            commitment_stake.unwrap_or(template.suggested_stake),
+            beneficiary,
+            None,
+            &bumps.initialize,
+            remaining_accounts,
+        )?;
+
+        let plan = &mut self.initialize.meditation_plan;
+        plan.penalty_bps = template.penalty_bps;
+        plan.template = template.key();
+
+        Ok(())
+    }
+}
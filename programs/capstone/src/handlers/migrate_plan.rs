@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::error::MeditationPlanError;
+use crate::state::{MeditationPlan, MeditationPlanV0};
+
+#[derive(Accounts)]
+pub struct MigratePlan<'info> {
+    /// Pays the rent for the larger account and the rent reserve - anyone can migrate a plan as its
+    /// contents are kept
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Can't be deserialized as a `MeditationPlan` until it is migrated - the discriminator
+    /// and layout are checked in the handler
+    #[account(mut, owner = crate::ID)]
+    pub meditation_plan: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigratePlan<'info> {
+    /// Rewrites a plan created before layout versioning in the current layout, growing the
+    /// account to fit the new fields and depositing the rent reserve of unsettled plans
+    pub fn migrate_plan(&mut self) -> Result<()> {
+        let plan = MeditationPlan::from(self.read_legacy_plan()?);
+
+        let space = MeditationPlan::DISCRIMINATOR.len() + MeditationPlan::INIT_SPACE;
+        let rent = Rent::get()?;
+        let mut additional_rent = rent
+            .minimum_balance(space)
+            .saturating_sub(rent.minimum_balance(MeditationPlanV0::SPACE));
+        // Legacy plans predate the rent reserve, so one still to be settled gets it like a new plan
+        if !plan.is_completed() {
+            additional_rent += MeditationPlan::rent_reserve()?;
+        }
+        let cpi_accounts = Transfer {
+            from: self.payer.to_account_info(),
+            to: self.meditation_plan.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), cpi_accounts);
+        transfer(cpi_ctx, additional_rent)?;
+
+        let plan_info = self.meditation_plan.to_account_info();
+        plan_info.realloc(space, false)?;
+        let mut data = plan_info.try_borrow_mut_data()?;
+        data.fill(0);
+        plan.try_serialize(&mut &mut data[..])
+    }
+
+    /// Versioned layouts are told apart by the version byte after the discriminator - only the
+    /// unversioned layout is recognised by its size
+    fn read_legacy_plan(&self) -> Result<MeditationPlanV0> {
+        let data = self.meditation_plan.try_borrow_data()?;
+        require!(
+            data.starts_with(MeditationPlan::DISCRIMINATOR),
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        let current_space = MeditationPlan::DISCRIMINATOR.len() + MeditationPlan::INIT_SPACE;
+        match data.len() {
+            MeditationPlanV0::SPACE => {
+                let mut body = &data[MeditationPlan::DISCRIMINATOR.len()..];
+                MeditationPlanV0::deserialize(&mut body)
+                    .map_err(|_| MeditationPlanError::InvalidPlanLayout.into())
+            }
+            len if len == current_space => match data.get(MeditationPlan::DISCRIMINATOR.len()) {
+                Some(&MeditationPlan::LAYOUT_VERSION) => {
+                    err!(MeditationPlanError::PlanAlreadyMigrated)
+                }
+                _ => err!(MeditationPlanError::InvalidPlanLayout),
+            },
+            _ => err!(MeditationPlanError::InvalidPlanLayout),
+        }
+    }
+}
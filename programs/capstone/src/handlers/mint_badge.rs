@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{allocate, assign, transfer, Allocate, Assign, Transfer};
+use anchor_spl::{
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_2022::{
+        initialize_mint2, mint_to, set_authority,
+        spl_token_2022::{extension::ExtensionType, instruction::AuthorityType, state::Mint},
+        InitializeMint2, MintTo, SetAuthority, Token2022,
+    },
+    token_interface::{
+        metadata_pointer_initialize, non_transferable_mint_initialize,
+        spl_pod::optional_keys::OptionalNonZeroPubkey,
+        spl_token_metadata_interface::state::{Field, TokenMetadata},
+        token_metadata_initialize, token_metadata_update_field, MetadataPointerInitialize,
+        NonTransferableMintInitialize, TokenMetadataInitialize, TokenMetadataUpdateField,
+    },
+};
+
+use crate::constants::{BADGE_NAME, BADGE_SYMBOL, BADGE_URI};
+use crate::error::MeditationPlanError;
+use crate::state::MeditationPlan;
+
+#[derive(Accounts)]
+pub struct MintBadge<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        constraint = meditation_plan.is_current_layout() @ MeditationPlanError::PlanNotMigrated,
+        constraint = meditation_plan.is_completed() @ MeditationPlanError::PlanNotCompleted,
+        has_one = owner @ MeditationPlanError::UnauthorizedAccess,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+
+    /// CHECK: Created in the handler as a Token-2022 mint with the NonTransferable and
+    /// MetadataPointer extensions - the seeds allow a single badge per plan
+    #[account(
+        mut,
+        seeds = [b"badge", meditation_plan.key().as_ref()],
+        bump,
+    )]
+    pub badge_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Created in the handler once the badge mint exists
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &owner.key(),
+            &badge_mint.key(),
+            &token_program.key()
+        ),
+    )]
+    pub owner_badge_ata: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MintBadge<'info> {
+    /// Mints the badge of a plan completed with every session attested. It is its own instruction
+    /// rather than part of the settlement, so a badge that can't be minted never holds up a payout.
+    pub fn mint_badge(&mut self, bumps: &MintBadgeBumps) -> Result<()> {
+        require!(
+            self.badge_mint.data_is_empty(),
+            MeditationPlanError::BadgeAlreadyMinted
+        );
+        require!(
+            self.meditation_plan.all_sessions_completed(),
+            MeditationPlanError::BadgeNotEarned
+        );
+
+        let plan_key = self.meditation_plan.key();
+        let badge_seeds = &[b"badge", plan_key.as_ref(), &[bumps.badge_mint]];
+        let badge_signer = &[&badge_seeds[..]];
+
+        let owner_key = self.meditation_plan.owner.key();
+        let id_bytes = self.meditation_plan.id.to_le_bytes();
+        let plan_seeds = &[
+            b"meditation_plan",
+            owner_key.as_ref(),
+            id_bytes.as_ref(),
+            &[self.meditation_plan.bump],
+        ];
+        let plan_signer = &[&plan_seeds[..]];
+
+        let metadata = self.badge_metadata();
+        self.create_badge_mint(&metadata, badge_signer)?;
+        self.initialize_badge_metadata(metadata, plan_signer)?;
+        self.mint_to_owner(plan_signer)
+    }
+
+    fn badge_metadata(&self) -> TokenMetadata {
+        let plan = &self.meditation_plan;
+        let additional_metadata = [
+            ("plan", plan.key().to_string()),
+            ("number_of_days", plan.number_of_days.to_string()),
+            ("daily_frequency", plan.daily_frequency.to_string()),
+            ("duration_minutes", plan.duration_minutes.to_string()),
+            ("commitment_stake", plan.commitment_stake.to_string()),
+            ("sessions_completed", plan.sessions_completed().to_string()),
+            ("total_sessions", plan.total_sessions().to_string()),
+            (
+                "completion_ratio_bps",
+                plan.completion_ratio_bps().to_string(),
+            ),
+            ("start_at", plan.start_at.to_string()),
+            ("end_at", plan.end_at.to_string()),
+            ("completed_at", plan.completed_at.to_string()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+
+        TokenMetadata {
+            update_authority: OptionalNonZeroPubkey(plan.key()),
+            mint: self.badge_mint.key(),
+            name: BADGE_NAME.to_string(),
+            symbol: BADGE_SYMBOL.to_string(),
+            uri: BADGE_URI.to_string(),
+            additional_metadata,
+        }
+    }
+
+    fn create_badge_mint(&self, metadata: &TokenMetadata, badge_signer: &[&[&[u8]]]) -> Result<()> {
+        let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+            ExtensionType::NonTransferable,
+            ExtensionType::MetadataPointer,
+        ])?;
+        // Token-2022 reallocates the mint for the metadata, but only if it can already pay the rent
+        let lamports = Rent::get()?.minimum_balance(space + metadata.tlv_size_of()?);
+
+        // Top up, allocate and assign rather than `create_account`, which fails once anyone has
+        // sent lamports to the predictable badge address
+        let shortfall = lamports.saturating_sub(self.badge_mint.lamports());
+        if shortfall > 0 {
+            let cpi_accounts = Transfer {
+                from: self.owner.to_account_info(),
+                to: self.badge_mint.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), cpi_accounts);
+            transfer(cpi_ctx, shortfall)?;
+        }
+
+        let cpi_accounts = Allocate {
+            account_to_allocate: self.badge_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            cpi_accounts,
+            badge_signer,
+        );
+        allocate(cpi_ctx, space as u64)?;
+
+        let cpi_accounts = Assign {
+            account_to_assign: self.badge_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            cpi_accounts,
+            badge_signer,
+        );
+        assign(cpi_ctx, &self.token_program.key())?;
+
+        // Extensions must be initialized before the mint itself
+        let cpi_accounts = NonTransferableMintInitialize {
+            token_program_id: self.token_program.to_account_info(),
+            mint: self.badge_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        non_transferable_mint_initialize(cpi_ctx)?;
+
+        let cpi_accounts = MetadataPointerInitialize {
+            token_program_id: self.token_program.to_account_info(),
+            mint: self.badge_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        metadata_pointer_initialize(
+            cpi_ctx,
+            Some(self.meditation_plan.key()),
+            Some(self.badge_mint.key()),
+        )?;
+
+        let cpi_accounts = InitializeMint2 {
+            mint: self.badge_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+        initialize_mint2(cpi_ctx, 0, &self.meditation_plan.key(), None)
+    }
+
+    fn initialize_badge_metadata(
+        &self,
+        metadata: TokenMetadata,
+        plan_signer: &[&[&[u8]]],
+    ) -> Result<()> {
+        let cpi_accounts = TokenMetadataInitialize {
+            program_id: self.token_program.to_account_info(),
+            metadata: self.badge_mint.to_account_info(),
+            update_authority: self.meditation_plan.to_account_info(),
+            mint_authority: self.meditation_plan.to_account_info(),
+            mint: self.badge_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            plan_signer,
+        );
+        token_metadata_initialize(cpi_ctx, metadata.name, metadata.symbol, metadata.uri)?;
+
+        for (key, value) in metadata.additional_metadata {
+            let cpi_accounts = TokenMetadataUpdateField {
+                program_id: self.token_program.to_account_info(),
+                metadata: self.badge_mint.to_account_info(),
+                update_authority: self.meditation_plan.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                cpi_accounts,
+                plan_signer,
+            );
+            token_metadata_update_field(cpi_ctx, Field::Key(key), value)?;
+        }
+
+        Ok(())
+    }
+
+    fn mint_to_owner(&self, plan_signer: &[&[&[u8]]]) -> Result<()> {
+        let cpi_accounts = Create {
+            payer: self.owner.to_account_info(),
+            associated_token: self.owner_badge_ata.to_account_info(),
+            authority: self.owner.to_account_info(),
+            mint: self.badge_mint.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            cpi_accounts,
+        );
+        create(cpi_ctx)?;
+
+        let cpi_accounts = MintTo {
+            mint: self.badge_mint.to_account_info(),
+            to: self.owner_badge_ata.to_account_info(),
+            authority: self.meditation_plan.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            plan_signer,
+        );
+        mint_to(cpi_ctx, 1)?;
+
+        // Drop the mint authority so the badge supply is fixed at one
+        let cpi_accounts = SetAuthority {
+            current_authority: self.meditation_plan.to_account_info(),
+            account_or_mint: self.badge_mint.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            cpi_accounts,
+            plan_signer,
+        );
+        set_authority(cpi_ctx, AuthorityType::MintTokens, None)
+    }
+}
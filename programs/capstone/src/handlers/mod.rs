@@ -1,7 +1,45 @@
 pub mod attest;
+pub mod cancel_plan;
+pub mod close_plan;
 pub mod complete;
+pub mod create_plan_template;
+pub mod extend_plan;
+pub mod increase_stake;
 pub mod initialize;
+pub mod initialize_config;
+pub mod initialize_from_template;
+pub mod migrate_plan;
+pub mod mint_badge;
+pub mod pause_plan;
+pub mod preview_settlement;
+pub mod register_charity;
+pub mod renew;
+pub mod resume_plan;
+pub mod settle_expired;
+pub mod submit_late_attestation;
+pub mod update_charity;
+pub mod update_config;
+pub mod withdraw_treasury;
 
 pub use attest::*;
+pub use cancel_plan::*;
+pub use close_plan::*;
 pub use complete::*;
+pub use create_plan_template::*;
+pub use extend_plan::*;
+pub use increase_stake::*;
 pub use initialize::*;
+pub use initialize_config::*;
+pub use initialize_from_template::*;
+pub use migrate_plan::*;
+pub use mint_badge::*;
+pub use pause_plan::*;
+pub use preview_settlement::*;
+pub use register_charity::*;
+pub use renew::*;
+pub use resume_plan::*;
+pub use settle_expired::*;
+pub use submit_late_attestation::*;
+pub use update_charity::*;
+pub use update_config::*;
+pub use withdraw_treasury::*;
@@ -0,0 +1,28 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+use crate::state::MeditationPlan;
+
+#[derive(Accounts)]
+pub struct PausePlan<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        has_one = owner @ MeditationPlanError::UnauthorizedAccess,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+}
+
+impl<'info> PausePlan<'info> {
+    pub fn pause_plan(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.meditation_plan.pause(now)
+    }
+}
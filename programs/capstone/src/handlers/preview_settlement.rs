@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+use crate::state::{MeditationPlan, ProjectedSettlement};
+
+#[derive(Accounts)]
+pub struct PreviewSettlement<'info> {
+    #[account(
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        constraint = meditation_plan.is_current_layout() @ MeditationPlanError::PlanNotMigrated,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+}
+
+impl<'info> PreviewSettlement<'info> {
+    /// Read-only - Anchor serializes the result into the transaction's return data, so anyone
+    /// can simulate it to see what the plan would settle to now
+    pub fn preview_settlement(&self) -> Result<ProjectedSettlement> {
+        let now = Clock::get()?.unix_timestamp;
+        self.meditation_plan.projected_settlement(now)
+    }
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::constants::USDC_MINT;
+use crate::error::MeditationPlanError;
+use crate::state::{Charity, CharityKind, Config};
+
+#[derive(Accounts)]
+pub struct RegisterCharity<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ MeditationPlanError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Wallet receiving donations - any account that can hold a USDC ATA
+    pub wallet: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Charity::DISCRIMINATOR.len() + Charity::INIT_SPACE,
+        seeds = [b"charity", wallet.key().as_ref()],
+        bump
+    )]
+    pub charity: Account<'info, Charity>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    // Created up front so settlement only pays for the charity's ATA if the charity closes it
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = mint,
+        associated_token::authority = wallet,
+        associated_token::token_program = token_program,
+    )]
+    pub charity_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterCharity<'info> {
+    pub fn register_charity(
+        &mut self,
+        name: String,
+        kind: CharityKind,
+        bumps: &RegisterCharityBumps,
+    ) -> Result<()> {
+        self.charity.set_inner(Charity {
+            bump: bumps.charity,
+            donations: 0,
+            is_active: true,
+            kind,
+            name,
+            total_donated: 0,
+            wallet: self.wallet.key(),
+        });
+        self.charity.validate()
+    }
+}
@@ -0,0 +1,270 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::adapters::{lending_adapter, LendingContext};
+use crate::constants::USDC_MINT;
+use crate::error::MeditationPlanError;
+use crate::handlers::PlanSettlement;
+use crate::state::{Charity, Config, MeditationPlan, Practitioner};
+
+#[derive(Accounts)]
+#[instruction(new_id: u64)]
+pub struct Renew<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        has_one = owner @ MeditationPlanError::UnauthorizedAccess,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = MeditationPlan::DISCRIMINATOR.len() + MeditationPlan::INIT_SPACE,
+        seeds = [b"meditation_plan", owner.key().as_ref(), new_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub new_meditation_plan: Account<'info, MeditationPlan>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Practitioner::DISCRIMINATOR.len() + Practitioner::INIT_SPACE,
+        seeds = [b"practitioner", owner.key().as_ref()],
+        bump,
+    )]
+    pub practitioner: Account<'info, Practitioner>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Wallet receiving any surplus payout - checked against the plan
+    #[account(address = meditation_plan.beneficiary @ MeditationPlanError::InvalidBeneficiary)]
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program,
+    )]
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = meditation_plan,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    /// Required when the plan donates its penalties - checked against the plan at settlement
+    #[account(mut)]
+    pub charity: Option<Account<'info, Charity>>,
+
+    /// CHECK: Wallet of the plan's charity - its ATA is checked against the charity at settlement
+    pub charity_wallet: Option<UncheckedAccount<'info>>,
+
+    // Created if the charity closed it since registering, so the settlement can't be blocked
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = charity_wallet,
+        associated_token::token_program = token_program,
+    )]
+    pub charity_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = new_meditation_plan,
+        associated_token::token_program = token_program,
+    )]
+    pub new_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Renew<'info> {
+    /// Settles the finished plan and opens the next one in a single transaction.
+    ///
+    /// The payout rolls straight into the new vault up to the new stake. Any surplus goes to the
+    /// beneficiary and any remainder of the new stake is deposited from the owner's ATA. The renewed
+    /// plan keeps the beneficiary, the charity while it is still active, and lending while the
+    /// config still allows it.
+    ///
+    /// Remaining accounts are the lending accounts of the finished plan followed by those of the
+    /// renewed plan, each starting with the lending program.
+    #[allow(clippy::too_many_arguments)]
+    pub fn renew(
+        &mut self,
+        new_id: u64,
+        number_of_days: u8,
+        daily_frequency: u8,
+        duration_minutes: u8,
+        commitment_stake: u64,
+        bumps: &RenewBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        MeditationPlan::validate_parameters(
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            commitment_stake,
+            self.mint.decimals,
+        )?;
+
+        let lending_program =
+            if self.meditation_plan.is_lending() && self.config.is_lending_enabled() {
+                self.config.lending_program
+            } else {
+                Pubkey::default()
+            };
+        // The renewed plan's lending accounts start at the second mention of its lending program
+        let split = remaining_accounts
+            .iter()
+            .skip(1)
+            .position(|account| account.key() == lending_program)
+            .map_or(remaining_accounts.len(), |index| index + 1);
+        let (settlement_accounts, lending_accounts) = remaining_accounts.split_at(split);
+
+        let mut settlement = PlanSettlement {
+            payer: self.owner.to_account_info(),
+            meditation_plan: &mut self.meditation_plan,
+            practitioner: &mut self.practitioner,
+            practitioner_bump: bumps.practitioner,
+            config: &self.config,
+            mint: &self.mint,
+            vault: &mut self.vault,
+            treasury: self.treasury.to_account_info(),
+            charity: self.charity.as_mut(),
+            charity_ata: self.charity_ata.as_ref().map(|ata| ata.to_account_info()),
+            token_program: &self.token_program,
+            system_program: &self.system_program,
+        };
+        let payout = settlement.settle(settlement_accounts)?;
+
+        let rollover = payout.min(commitment_stake);
+        settlement.transfer_from_vault(self.new_vault.to_account_info(), rollover)?;
+        settlement
+            .transfer_from_vault(self.beneficiary_ata.to_account_info(), payout - rollover)?;
+        // The new plan inherits the rent reserve instead of the owner depositing a fresh one
+        settlement.release_rent_reserve(&self.new_meditation_plan.to_account_info())?;
+
+        let start_at = Clock::get()?.unix_timestamp;
+        self.new_meditation_plan.set_inner(MeditationPlan::new(
+            self.owner.key(),
+            self.meditation_plan.beneficiary,
+            new_id,
+            bumps.new_meditation_plan,
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            commitment_stake,
+            start_at,
+            lending_program,
+        ));
+        if let Some(charity) = self
+            .charity
+            .as_ref()
+            .filter(|charity| charity.is_active && charity.key() == self.meditation_plan.charity)
+        {
+            self.new_meditation_plan.charity = charity.key();
+        }
+
+        self.deposit(commitment_stake - rollover)?;
+
+        if self.new_meditation_plan.is_lending() {
+            self.deposit_to_lending(commitment_stake, lending_accounts)?;
+        }
+
+        Ok(())
+    }
+
+    fn deposit(&self, amount: u64) -> Result<()> {
+        if amount < 1 {
+            return Ok(());
+        }
+
+        let transfer_accounts = TransferChecked {
+            from: self.owner_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.new_vault.to_account_info(),
+            authority: self.owner.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), transfer_accounts);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+
+    fn deposit_to_lending(
+        &self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let owner_key = self.owner.key();
+        let id_bytes = self.new_meditation_plan.id.to_le_bytes();
+        let seeds = &[
+            b"meditation_plan",
+            owner_key.as_ref(),
+            id_bytes.as_ref(),
+            &[self.new_meditation_plan.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let adapter = lending_adapter(
+            self.new_meditation_plan.lending_program,
+            LendingContext {
+                payer: self.owner.to_account_info(),
+                meditation_plan: self.new_meditation_plan.to_account_info(),
+                mint: self.mint.to_account_info(),
+                vault: self.new_vault.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+                remaining_accounts,
+            },
+        )?;
+        adapter.deposit(amount, signer)
+    }
+}
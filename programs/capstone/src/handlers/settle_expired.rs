@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{
+        create, get_associated_token_address_with_program_id, AssociatedToken, Create,
+    },
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::constants::USDC_MINT;
+use crate::error::MeditationPlanError;
+use crate::handlers::PlanSettlement;
+use crate::state::{Charity, Config, MeditationPlan, Practitioner};
+
+#[derive(Accounts)]
+pub struct SettleExpired<'info> {
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    /// CHECK: Plan owner receiving any unused rent reserve - checked against the plan with `has_one`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        has_one = owner @ MeditationPlanError::UnauthorizedAccess,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        space = Practitioner::DISCRIMINATOR.len() + Practitioner::INIT_SPACE,
+        seeds = [b"practitioner", owner.key().as_ref()],
+        bump,
+    )]
+    pub practitioner: Account<'info, Practitioner>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Wallet receiving the payout - checked against the plan
+    #[account(address = meditation_plan.beneficiary @ MeditationPlanError::InvalidBeneficiary)]
+    pub beneficiary: UncheckedAccount<'info>,
+
+    /// CHECK: Created in the handler if needed so the keeper can be reimbursed for the rent
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &beneficiary.key(),
+            &mint.key(),
+            &token_program.key()
+        ),
+    )]
+    pub beneficiary_ata: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = meditation_plan,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Created in the handler if needed so the keeper can be reimbursed for the rent
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &config.key(),
+            &mint.key(),
+            &token_program.key()
+        ),
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// Required when the plan donates its penalties - checked against the plan at settlement
+    #[account(mut)]
+    pub charity: Option<Account<'info, Charity>>,
+
+    /// CHECK: Wallet of the plan's charity - its ATA is checked against the charity at settlement
+    pub charity_wallet: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Created in the handler if the charity closed it since registering, so the settlement
+    /// can't be blocked - checked against the charity at settlement
+    #[account(mut)]
+    pub charity_ata: Option<UncheckedAccount<'info>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleExpired<'info> {
+    /// Runs the same settlement as `complete` for a plan its owner never came back to.
+    ///
+    /// The plan's rent reserve reimburses the keeper for every account it has to create, and
+    /// whatever is left goes back to the owner.
+    pub fn settle_expired(
+        &mut self,
+        bumps: &SettleExpiredBumps,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require_gt!(
+            now,
+            self.meditation_plan.settlement_deadline(),
+            MeditationPlanError::GracePeriodActive
+        );
+
+        // A practitioner without an owner was just created by `init_if_needed`
+        let mut keeper_rent = if self.practitioner.owner == Pubkey::default() {
+            let space = self.practitioner.to_account_info().data_len();
+            Rent::get()?.minimum_balance(space)
+        } else {
+            0
+        };
+        keeper_rent += self.create_ata_if_needed(&self.beneficiary_ata, &self.beneficiary)?;
+        keeper_rent += self.create_ata_if_needed(&self.treasury, &self.config.to_account_info())?;
+        if let Some(charity_ata) = &self.charity_ata {
+            let charity_wallet = self
+                .charity_wallet
+                .as_ref()
+                .ok_or(MeditationPlanError::InvalidCharity)?;
+            keeper_rent += self.create_ata_if_needed(charity_ata, charity_wallet)?;
+        }
+
+        let mut settlement = PlanSettlement {
+            payer: self.keeper.to_account_info(),
+            meditation_plan: &mut self.meditation_plan,
+            practitioner: &mut self.practitioner,
+            practitioner_bump: bumps.practitioner,
+            config: &self.config,
+            mint: &self.mint,
+            vault: &mut self.vault,
+            treasury: self.treasury.to_account_info(),
+            charity: self.charity.as_mut(),
+            charity_ata: self
+                .charity_ata
+                .as_ref()
+                .map(|charity_ata| charity_ata.to_account_info()),
+            token_program: &self.token_program,
+            system_program: &self.system_program,
+        };
+        let payout = settlement.settle(remaining_accounts)?;
+        settlement.transfer_from_vault(self.beneficiary_ata.to_account_info(), payout)?;
+
+        settlement.reimburse_from_rent_reserve(&self.keeper.to_account_info(), keeper_rent)?;
+        settlement.release_rent_reserve(&self.owner.to_account_info())
+    }
+
+    /// Creates the ATA at the given address if it doesn't exist yet, paid by the keeper, and
+    /// returns the rent the keeper paid
+    fn create_ata_if_needed(
+        &self,
+        associated_token: &AccountInfo<'info>,
+        authority: &AccountInfo<'info>,
+    ) -> Result<u64> {
+        if !associated_token.data_is_empty() {
+            return Ok(0);
+        }
+
+        let keeper_lamports = self.keeper.lamports();
+        let cpi_accounts = Create {
+            payer: self.keeper.to_account_info(),
+            associated_token: associated_token.clone(),
+            authority: authority.clone(),
+            mint: self.mint.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            cpi_accounts,
+        );
+        create(cpi_ctx)?;
+        Ok(keeper_lamports.saturating_sub(self.keeper.lamports()))
+    }
+}
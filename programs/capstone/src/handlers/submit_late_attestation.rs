@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+use crate::state::{Config, MeditationPlan, Practitioner};
+
+#[derive(Accounts)]
+pub struct SubmitLateAttestation<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Vouches for sessions that failed to land on-chain, e.g. from an offline device
+    #[account(address = config.attestation_oracle @ MeditationPlanError::UnauthorizedOracle)]
+    pub oracle: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"meditation_plan",
+            meditation_plan.owner.key().as_ref(),
+            meditation_plan.id.to_le_bytes().as_ref()
+        ],
+        bump = meditation_plan.bump,
+        has_one = owner @ MeditationPlanError::UnauthorizedAccess,
+    )]
+    pub meditation_plan: Account<'info, MeditationPlan>,
+
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Practitioner::DISCRIMINATOR.len() + Practitioner::INIT_SPACE,
+        seeds = [b"practitioner", owner.key().as_ref()],
+        bump,
+    )]
+    pub practitioner: Account<'info, Practitioner>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SubmitLateAttestation<'info> {
+    /// Attests a session during the dispute window after the plan ends. The attestation records
+    /// the oracle as the attester.
+    pub fn submit_late_attestation(
+        &mut self,
+        started_at: i64,
+        ended_at: i64,
+        bumps: &SubmitLateAttestationBumps,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        self.meditation_plan
+            .save_late_attestation(self.oracle.key(), started_at, ended_at, now)?;
+
+        self.practitioner
+            .initialize_if_needed(self.meditation_plan.owner, bumps.practitioner);
+        self.practitioner.record_session(started_at, ended_at)
+    }
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+use crate::state::{Charity, Config};
+
+#[derive(Accounts)]
+pub struct UpdateCharity<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ MeditationPlanError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"charity", charity.wallet.as_ref()],
+        bump = charity.bump,
+    )]
+    pub charity: Account<'info, Charity>,
+}
+
+impl<'info> UpdateCharity<'info> {
+    pub fn update_charity(&mut self, is_active: bool) -> Result<()> {
+        self.charity.is_active = is_active;
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ MeditationPlanError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(
+        &mut self,
+        lending_program: Pubkey,
+        owner_yield_bps: u16,
+        penalty_fee_bps: u16,
+        yield_fee_bps: u16,
+        attestation_oracle: Pubkey,
+    ) -> Result<()> {
+        self.config.attestation_oracle = attestation_oracle;
+        self.config.lending_program = lending_program;
+        self.config.owner_yield_bps = owner_yield_bps;
+        self.config.penalty_fee_bps = penalty_fee_bps;
+        self.config.yield_fee_bps = yield_fee_bps;
+        self.config.validate()
+    }
+}
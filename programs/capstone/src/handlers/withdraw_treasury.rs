@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::constants::USDC_MINT;
+use crate::error::MeditationPlanError;
+use crate::state::Config;
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = admin @ MeditationPlanError::UnauthorizedAdmin,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mint::token_program = token_program,
+        constraint = mint.key() == USDC_MINT @ MeditationPlanError::InvalidMint
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawTreasury<'info> {
+    /// Moves collected protocol fees to any token account chosen by the admin
+    pub fn withdraw_treasury(&mut self, amount: u64) -> Result<()> {
+        require_gt!(amount, 0, MeditationPlanError::InvalidWithdrawalAmount);
+        require_gte!(
+            self.treasury.amount,
+            amount,
+            MeditationPlanError::InvalidWithdrawalAmount
+        );
+
+        let seeds = &[b"config".as_ref(), &[self.config.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: self.treasury.to_account_info(),
+            to: self.destination.to_account_info(),
+            mint: self.mint.to_account_info(),
+            authority: self.config.to_account_info(),
+        };
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+}
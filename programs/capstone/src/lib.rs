@@ -1,8 +1,10 @@
 #![allow(unexpected_cfgs)]
 #![allow(deprecated)]
 
+pub mod adapters;
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod handlers;
 pub mod state;
 
@@ -18,13 +20,16 @@ declare_id!("Bvw5aYMCJDM1136hC5GLqmtq1LbsqSKEgC4owCQj9ZYm");
 pub mod capstone {
     use super::*;
 
-    pub fn initialize(
-        ctx: Context<Initialize>,
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
         id: u64,
         number_of_days: u8,
         daily_frequency: u8,
         duration_minutes: u8,
         commitment_stake: u64,
+        beneficiary: Option<Pubkey>,
+        start_at: Option<i64>,
     ) -> Result<()> {
         ctx.accounts.initialize(
             id,
@@ -32,16 +37,182 @@ pub mod capstone {
             daily_frequency,
             duration_minutes,
             commitment_stake,
+            beneficiary,
+            start_at,
             &ctx.bumps,
+            ctx.remaining_accounts,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_plan_template(
+        ctx: Context<CreatePlanTemplate>,
+        id: u64,
+        name: String,
+        number_of_days: u8,
+        daily_frequency: u8,
+        duration_minutes: u8,
+        suggested_stake: u64,
+        penalty_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.create_plan_template(
+            id,
+            name,
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            suggested_stake,
+            penalty_bps,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn initialize_from_template<'info>(
+        ctx: Context<'_, '_, '_, 'info, InitializeFromTemplate<'info>>,
+        id: u64,
+        commitment_stake: Option<u64>,
+        beneficiary: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.initialize_from_template(
+            id,
+            commitment_stake,
+            beneficiary,
+            &ctx.bumps,
+            ctx.remaining_accounts,
         )
     }
 
     pub fn attest(ctx: Context<Attest>, started_at: i64, ended_at: i64) -> Result<()> {
-        ctx.accounts.attest(started_at, ended_at)
+        ctx.accounts.attest(started_at, ended_at, &ctx.bumps)
+    }
+
+    pub fn submit_late_attestation(
+        ctx: Context<SubmitLateAttestation>,
+        started_at: i64,
+        ended_at: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .submit_late_attestation(started_at, ended_at, &ctx.bumps)
+    }
+
+    pub fn complete<'info>(ctx: Context<'_, '_, '_, 'info, Complete<'info>>) -> Result<()> {
+        ctx.accounts.complete(&ctx.bumps, ctx.remaining_accounts)
     }
 
-    pub fn complete(ctx: Context<Complete>) -> Result<()> {
-        ctx.accounts.complete()
+    pub fn increase_stake<'info>(
+        ctx: Context<'_, '_, '_, 'info, IncreaseStake<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.increase_stake(amount, ctx.remaining_accounts)
+    }
+
+    pub fn extend_plan(ctx: Context<ExtendPlan>, additional_days: u8) -> Result<()> {
+        ctx.accounts.extend_plan(additional_days)
+    }
+
+    pub fn pause_plan(ctx: Context<PausePlan>) -> Result<()> {
+        ctx.accounts.pause_plan()
+    }
+
+    pub fn resume_plan(ctx: Context<ResumePlan>) -> Result<()> {
+        ctx.accounts.resume_plan()
+    }
+
+    pub fn cancel_plan<'info>(ctx: Context<'_, '_, '_, 'info, CancelPlan<'info>>) -> Result<()> {
+        ctx.accounts.cancel_plan(ctx.remaining_accounts)
+    }
+
+    pub fn close_plan(ctx: Context<ClosePlan>) -> Result<()> {
+        ctx.accounts.close_plan()
+    }
+
+    pub fn renew<'info>(
+        ctx: Context<'_, '_, '_, 'info, Renew<'info>>,
+        new_id: u64,
+        number_of_days: u8,
+        daily_frequency: u8,
+        duration_minutes: u8,
+        commitment_stake: u64,
+    ) -> Result<()> {
+        ctx.accounts.renew(
+            new_id,
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            commitment_stake,
+            &ctx.bumps,
+            ctx.remaining_accounts,
+        )
+    }
+
+    pub fn settle_expired<'info>(
+        ctx: Context<'_, '_, '_, 'info, SettleExpired<'info>>,
+    ) -> Result<()> {
+        ctx.accounts
+            .settle_expired(&ctx.bumps, ctx.remaining_accounts)
+    }
+
+    pub fn mint_badge(ctx: Context<MintBadge>) -> Result<()> {
+        ctx.accounts.mint_badge(&ctx.bumps)
+    }
+
+    pub fn migrate_plan(ctx: Context<MigratePlan>) -> Result<()> {
+        ctx.accounts.migrate_plan()
+    }
+
+    pub fn preview_settlement(ctx: Context<PreviewSettlement>) -> Result<ProjectedSettlement> {
+        ctx.accounts.preview_settlement()
+    }
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        lending_program: Pubkey,
+        owner_yield_bps: u16,
+        penalty_fee_bps: u16,
+        yield_fee_bps: u16,
+        attestation_oracle: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.initialize_config(
+            lending_program,
+            owner_yield_bps,
+            penalty_fee_bps,
+            yield_fee_bps,
+            attestation_oracle,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        lending_program: Pubkey,
+        owner_yield_bps: u16,
+        penalty_fee_bps: u16,
+        yield_fee_bps: u16,
+        attestation_oracle: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.update_config(
+            lending_program,
+            owner_yield_bps,
+            penalty_fee_bps,
+            yield_fee_bps,
+            attestation_oracle,
+        )
+    }
+
+    pub fn register_charity(
+        ctx: Context<RegisterCharity>,
+        name: String,
+        kind: CharityKind,
+    ) -> Result<()> {
+        ctx.accounts.register_charity(name, kind, &ctx.bumps)
+    }
+
+    pub fn update_charity(ctx: Context<UpdateCharity>, is_active: bool) -> Result<()> {
+        ctx.accounts.update_charity(is_active)
+    }
+
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_treasury(amount)
     }
 }
 
@@ -50,3 +221,6 @@ mod tests;
 
 #[cfg(test)]
 mod test_helpers;
+
+#[cfg(test)]
+mod scenario;
@@ -0,0 +1,419 @@
+//! A small timeline language for multi-day plan tests. A scenario is a list of events separated by
+//! `;` or new lines, each happening on a plan day:
+//!
+//! ```text
+//! day 0 create 7x2x10m 50 USDC    // 7 days, 2 sessions a day of 10 minutes, 50 USDC stake
+//! days 0-2 attest 1               // one session on each of days 0, 1 and 2
+//! day 3 attest 3 fails DailyFrequencyExceeded
+//! day 3 pause; day 5 resume
+//! day 5 extend 2                  // add 2 days
+//! day 5 stake 20 USDC             // increase the stake
+//! day 9 attest 1 fails PlanExpired
+//! day 9 late attest day 6         // oracle co-signed session on day 6
+//! day 10 complete                 // settled by the owner
+//! day 12 settle                   // settled by a keeper
+//! ```
+//!
+//! Alice owns the plan and Bob is the keeper. Days count from the plan's start, the clock only
+//! moves forward, and an event without `fails <Error>` must succeed.
+
+use std::collections::HashMap;
+
+use litesvm::LiteSVM;
+use solana_kite::{get_token_account_balance, SolanaKiteError};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::test_helpers::{
+    execute_attest, execute_complete, execute_extend_plan, execute_increase_stake,
+    execute_initialize, execute_pause_plan, execute_resume_plan, execute_settle_expired,
+    execute_submit_late_attestation, generate_id, get_meditation_plan, set_clock, TestHarness,
+    STARTED_AT, USDC_TOKEN,
+};
+use crate::{MeditationPlan, DAY_IN_SECONDS, HOUR_IN_SECONDS};
+
+/// Events other than sessions happen this long after the start of their day
+const EVENT_OFFSET: i64 = 60;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Action {
+    Create {
+        number_of_days: u8,
+        daily_frequency: u8,
+        duration_minutes: u8,
+        commitment_stake: u64,
+    },
+    Attest {
+        sessions: u8,
+    },
+    LateAttest {
+        day: i64,
+    },
+    Pause,
+    Resume,
+    Extend {
+        days: u8,
+    },
+    IncreaseStake {
+        amount: u64,
+    },
+    Complete,
+    SettleExpired,
+}
+
+#[derive(Clone, Debug)]
+struct Event {
+    day: i64,
+    action: Action,
+    /// Name of the program error the event must fail with
+    fails: Option<String>,
+    line: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    events: Vec<Event>,
+}
+
+/// Parses `50` or `12.5` as USDC base units
+fn parse_usdc(amount: &str) -> u64 {
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let whole: u64 = whole.parse().expect("Amounts should be numbers");
+    let fraction = format!("{fraction:0<6}");
+    assert!(fraction.len() == 6, "USDC has 6 decimals: {amount}");
+    whole * USDC_TOKEN + fraction.parse::<u64>().unwrap()
+}
+
+/// Parses `7x2x10m` as days, sessions a day and minutes per session
+fn parse_plan(plan: &str) -> (u8, u8, u8) {
+    let parts: Vec<&str> = plan.trim_end_matches('m').split('x').collect();
+    match parts[..] {
+        [days, frequency, minutes] => (
+            days.parse().expect("Plan days should be a number"),
+            frequency
+                .parse()
+                .expect("Daily frequency should be a number"),
+            minutes
+                .parse()
+                .expect("Duration should be a number of minutes"),
+        ),
+        _ => panic!("Plans are written <days>x<sessions a day>x<minutes>m: {plan}"),
+    }
+}
+
+fn parse_action(words: &[&str], line: &str) -> Action {
+    match words {
+        ["create", plan, amount, "USDC"] => {
+            let (number_of_days, daily_frequency, duration_minutes) = parse_plan(plan);
+            Action::Create {
+                number_of_days,
+                daily_frequency,
+                duration_minutes,
+                commitment_stake: parse_usdc(amount),
+            }
+        }
+        ["attest", sessions] => Action::Attest {
+            sessions: sessions.parse().expect("Sessions should be a number"),
+        },
+        ["late", "attest", "day", day] => Action::LateAttest {
+            day: day.parse().expect("Days should be numbers"),
+        },
+        ["pause"] => Action::Pause,
+        ["resume"] => Action::Resume,
+        ["extend", days] => Action::Extend {
+            days: days.parse().expect("Days should be a number"),
+        },
+        ["stake", amount, "USDC"] => Action::IncreaseStake {
+            amount: parse_usdc(amount),
+        },
+        ["complete"] => Action::Complete,
+        ["settle"] => Action::SettleExpired,
+        _ => panic!("Unknown scenario event: {line}"),
+    }
+}
+
+impl Scenario {
+    pub fn parse(timeline: &str) -> Self {
+        let mut events = vec![];
+        for line in timeline.split([';', '\n']) {
+            let line = line.split("//").next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut words: Vec<&str> = line.split_whitespace().collect();
+
+            let fails = match words.iter().position(|word| *word == "fails") {
+                Some(index) => {
+                    let error = words.get(index + 1).expect("`fails` needs an error name");
+                    let error = error.to_string();
+                    words.truncate(index);
+                    Some(error)
+                }
+                None => None,
+            };
+
+            let days = match words[..2] {
+                ["day", day] => {
+                    let day = day.parse().expect("Days should be numbers");
+                    day..=day
+                }
+                ["days", range] => {
+                    let (first, last) = range.split_once('-').expect("Day ranges are written 0-6");
+                    first.parse().expect("Days should be numbers")
+                        ..=last.parse().expect("Days should be numbers")
+                }
+                _ => panic!("Scenario events start with `day N` or `days A-B`: {line}"),
+            };
+            let action = parse_action(&words[2..], line);
+            for day in days {
+                events.push(Event {
+                    day,
+                    action: action.clone(),
+                    fails: fails.clone(),
+                    line: line.to_string(),
+                });
+            }
+        }
+        Scenario { events }
+    }
+
+    /// Plays the timeline against a fresh `TestHarness`
+    pub fn run(&self) -> ScenarioOutcome {
+        let (svm, harness) = TestHarness::new();
+        let starting_balances = [harness.alice.pubkey(), harness.bob.pubkey()]
+            .into_iter()
+            .map(|wallet| (wallet, usdc_balance(&svm, &harness, wallet)))
+            .collect();
+        let mut runner = Runner {
+            svm,
+            harness,
+            meditation_plan: None,
+            start_at: STARTED_AT,
+            now: STARTED_AT,
+            sessions_per_day: HashMap::new(),
+        };
+        for event in &self.events {
+            runner.play(event);
+        }
+
+        let (meditation_plan, vault) = runner
+            .meditation_plan
+            .expect("Scenarios should create a plan");
+        ScenarioOutcome {
+            svm: runner.svm,
+            harness: runner.harness,
+            meditation_plan,
+            vault,
+            starting_balances,
+        }
+    }
+}
+
+fn expect_success(event: &Event, result: Result<(), SolanaKiteError>) {
+    if let Err(error) = result {
+        panic!("`{}` should have succeeded: {error}", event.line);
+    }
+}
+
+fn usdc_balance(svm: &LiteSVM, harness: &TestHarness, wallet: Pubkey) -> u64 {
+    get_token_account_balance(
+        svm,
+        &get_associated_token_address(&wallet, &harness.usdc_mint),
+    )
+    .unwrap_or(0)
+}
+
+struct Runner {
+    svm: LiteSVM,
+    harness: TestHarness,
+    meditation_plan: Option<(Pubkey, Pubkey)>,
+    /// When day 0 starts - the plan's `start_at` once it is created
+    start_at: i64,
+    now: i64,
+    sessions_per_day: HashMap<i64, u8>,
+}
+
+impl Runner {
+    fn plan(&self, event: &Event) -> (Pubkey, Pubkey) {
+        self.meditation_plan
+            .unwrap_or_else(|| panic!("`{}` needs a plan - create one first", event.line))
+    }
+
+    fn day_start(&self, day: i64) -> i64 {
+        self.start_at + day * DAY_IN_SECONDS
+    }
+
+    /// Moves the clock to `at`, or leaves it if it is already later
+    fn advance_to(&mut self, at: i64) -> i64 {
+        self.now = self.now.max(at);
+        set_clock(&mut self.svm, self.now);
+        self.now
+    }
+
+    /// The next session on a plan day, an hour after the previous one
+    fn next_session(&mut self, day: i64) -> (i64, i64) {
+        let plan = self.plan_account();
+        let slot = self.sessions_per_day.entry(day).or_insert(0);
+        let started_at = self.start_at + day * DAY_IN_SECONDS + *slot as i64 * HOUR_IN_SECONDS;
+        *slot += 1;
+        (started_at, started_at + plan.duration_minutes as i64 * 60)
+    }
+
+    fn plan_account(&mut self) -> MeditationPlan {
+        let (meditation_plan, _vault) = self.meditation_plan.expect("The plan should exist");
+        get_meditation_plan(&mut self.svm, &meditation_plan).1
+    }
+
+    fn play(&mut self, event: &Event) {
+        let result = match event.action {
+            Action::Attest { sessions } => {
+                let (meditation_plan, _vault) = self.plan(event);
+                let mut result = Ok(());
+                for session in 0..sessions {
+                    let (started_at, ended_at) = self.next_session(event.day);
+                    self.advance_to(ended_at + 1);
+                    result = execute_attest(
+                        &mut self.svm,
+                        &self.harness.alice,
+                        meditation_plan,
+                        started_at,
+                        ended_at,
+                    );
+                    // Only the last session may fail
+                    if session + 1 < sessions {
+                        expect_success(event, result);
+                        result = Ok(());
+                    }
+                }
+                result
+            }
+            _ => {
+                let at = self.day_start(event.day) + EVENT_OFFSET;
+                self.advance_to(at);
+                self.play_at(event)
+            }
+        };
+
+        match &event.fails {
+            None => expect_success(event, result),
+            Some(error) => {
+                let message = result
+                    .expect_err(&format!("`{}` should have failed", event.line))
+                    .to_string();
+                assert!(
+                    message.contains(&format!("Error Code: {error}")),
+                    "`{}` failed with the wrong error: {message}",
+                    event.line
+                );
+            }
+        }
+    }
+
+    fn play_at(&mut self, event: &Event) -> Result<(), SolanaKiteError> {
+        if let Action::Create { .. } = event.action {
+            return self.create(event);
+        }
+        let (meditation_plan, vault) = self.plan(event);
+        if let Action::LateAttest { day } = event.action {
+            let (started_at, ended_at) = self.next_session(day);
+            return execute_submit_late_attestation(
+                &mut self.svm,
+                &self.harness.alice,
+                &self.harness.oracle,
+                meditation_plan,
+                started_at,
+                ended_at,
+            );
+        }
+
+        let svm = &mut self.svm;
+        let harness = &self.harness;
+        match event.action {
+            Action::Create { .. } | Action::Attest { .. } | Action::LateAttest { .. } => {
+                unreachable!("Played separately")
+            }
+            Action::Pause => execute_pause_plan(svm, &harness.alice, meditation_plan),
+            Action::Resume => execute_resume_plan(svm, &harness.alice, meditation_plan),
+            Action::Extend { days } => {
+                execute_extend_plan(svm, &harness.alice, meditation_plan, days)
+            }
+            Action::IncreaseStake { amount } => execute_increase_stake(
+                svm,
+                harness.usdc_mint,
+                &harness.alice,
+                harness.alice_usdc_account,
+                meditation_plan,
+                vault,
+                amount,
+            ),
+            Action::Complete => execute_complete(
+                svm,
+                harness.usdc_mint,
+                &harness.alice,
+                harness.alice_usdc_account,
+                meditation_plan,
+                vault,
+            ),
+            Action::SettleExpired => execute_settle_expired(
+                svm,
+                harness.usdc_mint,
+                &harness.bob,
+                harness.alice.pubkey(),
+                meditation_plan,
+                vault,
+            ),
+        }
+    }
+    fn create(&mut self, event: &Event) -> Result<(), SolanaKiteError> {
+        let Action::Create {
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            commitment_stake,
+        } = event.action
+        else {
+            unreachable!("Only creates a plan")
+        };
+        let (meditation_plan, _bump, vault) = execute_initialize(
+            &mut self.svm,
+            self.harness.usdc_mint,
+            &self.harness.alice,
+            self.harness.alice_usdc_account,
+            generate_id(),
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            commitment_stake,
+        )?;
+        self.meditation_plan = Some((meditation_plan, vault));
+        // Later days count from the plan's start
+        self.start_at = self.now;
+        Ok(())
+    }
+}
+
+/// The state a scenario left behind, for its assertions
+pub struct ScenarioOutcome {
+    pub svm: LiteSVM,
+    pub harness: TestHarness,
+    pub meditation_plan: Pubkey,
+    pub vault: Pubkey,
+    starting_balances: HashMap<Pubkey, u64>,
+}
+
+impl ScenarioOutcome {
+    pub fn plan(&mut self) -> MeditationPlan {
+        get_meditation_plan(&mut self.svm, &self.meditation_plan).1
+    }
+
+    pub fn vault_balance(&self) -> u64 {
+        get_token_account_balance(&self.svm, &self.vault).unwrap_or(0)
+    }
+
+    /// How much the wallet's USDC balance moved over the scenario
+    pub fn usdc_change(&self, wallet: Pubkey) -> i64 {
+        let before = self.starting_balances.get(&wallet).copied().unwrap_or(0);
+        usdc_balance(&self.svm, &self.harness, wallet) as i64 - before as i64
+    }
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+
+/// Why the recipient is on the approved list
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum CharityKind {
+    /// A cause the owner wants to support
+    Charity,
+    /// A cause the owner doesn't want to support, used as a stronger motivator to finish the plan
+    AntiCharity,
+}
+
+/// Approved recipient for forfeited stakes, with a running tally of the donations it received
+#[account]
+#[derive(InitSpace)]
+pub struct Charity {
+    pub bump: u8,
+    /// Number of settled plans that donated to the charity
+    pub donations: u64,
+    /// Inactive charities keep receiving from existing plans but can't be chosen for new ones
+    pub is_active: bool,
+    /// Set at registration and never changed, so a plan's choice can't turn into the other kind
+    pub kind: CharityKind,
+    #[max_len(32)]
+    pub name: String,
+    pub total_donated: u64,
+    /// Wallet receiving the donations - its ATA is created at registration
+    pub wallet: Pubkey,
+}
+
+impl Charity {
+    pub const MAX_NAME_LEN: usize = 32;
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            !self.name.is_empty() && self.name.len() <= Self::MAX_NAME_LEN,
+            MeditationPlanError::InvalidCharityName
+        );
+        Ok(())
+    }
+
+    pub fn record_donation(&mut self, amount: u64) -> Result<()> {
+        self.donations = self
+            .donations
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.total_donated = self
+            .total_donated
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
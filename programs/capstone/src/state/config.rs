@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+
+/// Protocol-wide settings managed by the program upgrade authority
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Oracle or partner co-signing late attestations - the default pubkey disables them
+    pub attestation_oracle: Pubkey,
+    pub bump: u8,
+    /// Approved lending program for yield-bearing vaults - the default pubkey disables yield
+    pub lending_program: Pubkey,
+    /// Share of accrued yield paid to the plan owner, in basis points
+    pub owner_yield_bps: u16,
+    /// Protocol fee on forfeited penalties, in basis points
+    pub penalty_fee_bps: u16,
+    /// Protocol fee on the accrued yield not paid to the owner, in basis points
+    pub yield_fee_bps: u16,
+}
+
+impl Config {
+    pub fn validate(&self) -> Result<()> {
+        for bps in [
+            self.owner_yield_bps,
+            self.penalty_fee_bps,
+            self.yield_fee_bps,
+        ] {
+            require_gte!(10_000, bps, MeditationPlanError::InvalidBasisPoints);
+        }
+        Ok(())
+    }
+
+    pub fn is_lending_enabled(&self) -> bool {
+        self.lending_program != Pubkey::default()
+    }
+
+    pub fn owner_yield_share(&self, accrued_yield: u64) -> Result<u64> {
+        Self::share(accrued_yield, self.owner_yield_bps)
+    }
+
+    /// Part of a settlement's penalties and protocol yield counted as the protocol's fee - the
+    /// protocol yield is the accrued yield left after the owner's share. The treasury collects
+    /// both in full, see `MeditationPlan::treasury_share`.
+    pub fn protocol_fee(&self, penalties: u64, protocol_yield: u64) -> Result<u64> {
+        Self::share(penalties, self.penalty_fee_bps)?
+            .checked_add(Self::share(protocol_yield, self.yield_fee_bps)?)
+            .ok_or(ProgramError::ArithmeticOverflow.into())
+    }
+
+    fn share(amount: u64, bps: u16) -> Result<u64> {
+        let share = (amount as u128)
+            .checked_mul(bps as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / 10_000;
+        Ok(share as u64)
+    }
+}
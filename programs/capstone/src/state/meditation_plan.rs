@@ -1,67 +1,383 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token::state::Account as SplTokenAccount;
 
-use crate::constants::DAY_IN_SECONDS;
+use crate::constants::{
+    DAY_IN_SECONDS, DISPUTE_WINDOW_SECONDS, MAX_PAUSE_SECONDS, MAX_SCHEDULE_SECONDS,
+    SETTLEMENT_GRACE_SECONDS,
+};
 use crate::error::MeditationPlanError;
-use crate::MeditationAttestation;
+use crate::state::{MeditationPlanV0, Practitioner};
+use crate::{MeditationAttestation, PlanStatus, ProjectedSettlement};
 
-#[account]
-#[derive(InitSpace)]
+/// Implements the account traits by hand rather than with `#[account]`, so plans still in the
+/// legacy layout are recognised by their size before their bytes can be misread as this one
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
 pub struct MeditationPlan {
+    /// Layout the account was written in, right after the discriminator so later layouts can be
+    /// told apart - plans written before versioning must go through `migrate_plan` before they
+    /// can be used
+    pub version: u8,
+    /// Yield earned on the principal while it was lent out
+    pub accrued_yield: u64,
     #[max_len(120)] // 4 times a day * max of 30 days
     pub attestations: Vec<MeditationAttestation>,
+    /// Wallet receiving the plan payouts - the owner unless another wallet was chosen at creation
+    pub beneficiary: Pubkey,
     pub bump: u8,
+    /// Charity or anti-charity receiving the penalties at settlement, chosen at initialize - the
+    /// default pubkey keeps them in the vault
+    pub charity: Pubkey,
     pub commitment_stake: u64,
+    pub completed_at: i64,
     pub daily_frequency: u8,
+    /// Penalties sent to the charity at settlement
+    pub donated: u64,
     pub duration_minutes: u8,
     pub end_at: i64,
+    /// Tokens found in the vault at settlement beyond the principal and yield (donations, airdrops)
+    pub external_deposits: u64,
     pub id: u64,
-    pub is_active: bool,
-    pub is_completed: bool,
+    /// Lending program holding the vault's tokens - the default pubkey means the vault is idle
+    pub lending_program: Pubkey,
     pub number_of_days: u8,
     pub owner: Pubkey,
+    /// When the current pause started - only meaningful while the plan is paused
+    pub paused_at: i64,
+    /// Total time the plan has been paused, which has been added to `end_at`
+    pub paused_seconds: i64,
     pub penalties: u64,
+    /// Share of the value of missed sessions forfeited at settlement, in basis points
+    pub penalty_bps: u16,
+    /// Part of the penalties and protocol yield the treasury collects at settlement that counts as
+    /// the protocol's fee
+    pub protocol_fee: u64,
+    /// Value credited for each attested session - rebalanced when the stake changes so earlier
+    /// sessions keep the rate they were credited at
+    pub reward_per_session: u64,
     pub rewards: u64,
     pub start_at: i64,
+    pub status: PlanStatus,
+    /// Template the plan was created from - the default pubkey if it was configured by hand
+    pub template: Pubkey,
+    /// Principal and yield missing from the vault at settlement, taken out of the settlement
+    /// instead of failing it
+    pub shortfall: u64,
+    /// Spare bytes at the end of the account, so fields can be added in front of them without
+    /// growing the account
+    pub reserved: [u8; 56],
+}
+
+impl Discriminator for MeditationPlan {
+    // The discriminator `#[account]` derives from "account:MeditationPlan"
+    const DISCRIMINATOR: &'static [u8] = &[91, 175, 121, 163, 120, 239, 105, 74];
+}
+
+impl Owner for MeditationPlan {
+    fn owner() -> Pubkey {
+        crate::ID
+    }
+}
+
+impl AccountSerialize for MeditationPlan {
+    fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> Result<()> {
+        writer
+            .write_all(Self::DISCRIMINATOR)
+            .map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+        AnchorSerialize::serialize(self, writer).map_err(|_| ErrorCode::AccountDidNotSerialize)?;
+        Ok(())
+    }
+}
+
+impl AccountDeserialize for MeditationPlan {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < Self::DISCRIMINATOR.len() {
+            return err!(ErrorCode::AccountDiscriminatorNotFound);
+        }
+        if &buf[..Self::DISCRIMINATOR.len()] != Self::DISCRIMINATOR {
+            return Err(
+                error!(ErrorCode::AccountDiscriminatorMismatch).with_account_name("MeditationPlan")
+            );
+        }
+        // The legacy layout starts with the attestation count where the version now is, so a
+        // legacy plan with one attestation would otherwise read as the current version
+        if buf.len() == MeditationPlanV0::SPACE {
+            return err!(MeditationPlanError::PlanNotMigrated);
+        }
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let mut data = &buf[Self::DISCRIMINATOR.len()..];
+        AnchorDeserialize::deserialize(&mut data)
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize.into())
+    }
 }
 
 impl MeditationPlan {
-    pub fn complete(&mut self) -> Result<()> {
-        let total_sessions = self.total_sessions();
-        let all_sessions_completed = self.attestations.len() >= total_sessions as usize;
-        let now = Clock::get()?.unix_timestamp;
-        let is_expired = now > self.end_at;
-        // Ensure the plan has ended
-        require!(
-            all_sessions_completed || is_expired,
-            MeditationPlanError::PlanNotEnded
-        );
+    pub const FULL_PENALTY_BPS: u16 = 10_000;
+    pub const LAYOUT_VERSION: u8 = 1;
 
-        self.is_completed = true;
-        self.is_active = false;
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        owner: Pubkey,
+        beneficiary: Pubkey,
+        id: u64,
+        bump: u8,
+        number_of_days: u8,
+        daily_frequency: u8,
+        duration_minutes: u8,
+        commitment_stake: u64,
+        start_at: i64,
+        lending_program: Pubkey,
+    ) -> Self {
+        let total_sessions = number_of_days as u64 * daily_frequency as u64;
+        Self {
+            version: Self::LAYOUT_VERSION,
+            accrued_yield: 0,
+            attestations: vec![],
+            beneficiary,
+            bump,
+            charity: Pubkey::default(),
+            commitment_stake,
+            completed_at: 0,
+            daily_frequency,
+            donated: 0,
+            duration_minutes,
+            end_at: start_at + (number_of_days as i64 * DAY_IN_SECONDS),
+            external_deposits: 0,
+            id,
+            lending_program,
+            number_of_days,
+            owner,
+            paused_at: 0,
+            paused_seconds: 0,
+            penalties: 0,
+            penalty_bps: Self::FULL_PENALTY_BPS,
+            protocol_fee: 0,
+            reward_per_session: commitment_stake.checked_div(total_sessions).unwrap_or(0),
+            rewards: 0,
+            start_at,
+            status: PlanStatus::Active,
+            template: Pubkey::default(),
+            shortfall: 0,
+            reserved: [0; 56],
+        }
+    }
 
-        if all_sessions_completed {
-            self.rewards = self.commitment_stake;
-            return Ok(());
-        } else if self.attestations.len() == 0 {
-            // If no sessions were completed, the entire stake is a penalty
-            self.penalties = self.commitment_stake;
-            self.rewards = 0;
-            return Ok(());
+    /// Checks a new plan starts now or at most `MAX_SCHEDULE_SECONDS` ahead, and keeps one starting
+    /// later `Scheduled` until `start_at`
+    pub fn schedule(&mut self, now: i64) -> Result<()> {
+        require_gte!(self.start_at, now, MeditationPlanError::InvalidStartTime);
+        require_gte!(
+            now.saturating_add(MAX_SCHEDULE_SECONDS),
+            self.start_at,
+            MeditationPlanError::InvalidStartTime
+        );
+        if self.start_at > now {
+            self.status = PlanStatus::Scheduled;
         }
 
-        // If not all sessions are completed, apply penalties
-        let completed_sessions = self.attestations.len() as u64;
-        let penalty_per_session = self.reward_per_session(total_sessions);
-        let penalties = penalty_per_session
-            .checked_mul(total_sessions - completed_sessions)
+        Ok(())
+    }
+
+    /// Lamports held on the plan on top of its own rent to pay for every account a keeper may have
+    /// to create at settlement - the beneficiary's, treasury's and charity's ATAs and the
+    /// owner's practitioner account
+    pub fn rent_reserve() -> Result<u64> {
+        let rent = Rent::get()?;
+        let token_accounts = rent
+            .minimum_balance(SplTokenAccount::LEN)
+            .checked_mul(3)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let practitioner =
+            rent.minimum_balance(Practitioner::DISCRIMINATOR.len() + Practitioner::INIT_SPACE);
+        Ok(token_accounts + practitioner)
+    }
+
+    /// Checks the plan parameters chosen by the owner, with stake limits in whole tokens of the mint
+    pub fn validate_parameters(
+        number_of_days: u8,
+        daily_frequency: u8,
+        duration_minutes: u8,
+        commitment_stake: u64,
+        mint_decimals: u8,
+    ) -> Result<()> {
+        require_gte!(number_of_days, 7, MeditationPlanError::InvalidNumberOfDays);
+        require_gte!(30, number_of_days, MeditationPlanError::InvalidNumberOfDays);
+
+        require_gte!(
+            daily_frequency,
+            1,
+            MeditationPlanError::InvalidDailyFrequency
+        );
+        require_gte!(
+            4,
+            daily_frequency,
+            MeditationPlanError::InvalidDailyFrequency
+        );
+
+        require_gte!(
+            duration_minutes,
+            5,
+            MeditationPlanError::InvalidDurationMinutes
+        );
+        require_gte!(
+            60,
+            duration_minutes,
+            MeditationPlanError::InvalidDurationMinutes
+        );
+
+        Self::validate_commitment_stake(commitment_stake, mint_decimals)
+    }
+
+    /// Stake must be between 10 and 500 whole tokens of the mint
+    pub fn validate_commitment_stake(commitment_stake: u64, mint_decimals: u8) -> Result<()> {
+        let decimals = 10u64
+            .checked_pow(mint_decimals.into())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let min_stake = (10u64)
+            .checked_mul(decimals)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        require_gte!(
+            commitment_stake,
+            min_stake,
+            MeditationPlanError::InvalidCommitmentStakeAmount
+        );
+
+        let max_stake = (500u64)
+            .checked_mul(decimals)
             .ok_or(ProgramError::ArithmeticOverflow)?;
+        require_gte!(
+            max_stake,
+            commitment_stake,
+            MeditationPlanError::InvalidCommitmentStakeAmount
+        );
+
+        Ok(())
+    }
+
+    /// Finalizes rewards and penalties. A plan with missed sessions can only settle after the
+    /// dispute window, so sessions that failed to land on-chain can still be attested late.
+    pub fn complete(&mut self, now: i64) -> Result<()> {
+        self.transition(PlanStatus::Completed, now)?;
+        self.completed_at = now;
+
+        let (rewards, penalties) = self.settlement()?;
+        self.rewards = rewards;
         self.penalties = penalties;
-        self.rewards = self
+
+        Ok(())
+    }
+
+    /// What the plan would settle to if it were completed at `now`, without changing it. Completed
+    /// plans return their final rewards and penalties.
+    pub fn projected_settlement(&self, now: i64) -> Result<ProjectedSettlement> {
+        let (rewards, penalties) = if self.is_completed() {
+            (self.rewards, self.penalties)
+        } else {
+            self.settlement()?
+        };
+
+        Ok(ProjectedSettlement {
+            missed_sessions: self.missed_sessions(now),
+            penalties,
+            remaining_sessions: self.remaining_sessions(),
+            rewards,
+        })
+    }
+
+    /// Rewards and penalties for the sessions attested so far
+    fn settlement(&self) -> Result<(u64, u64)> {
+        if self.all_sessions_completed() {
+            return Ok((self.commitment_stake, 0));
+        }
+
+        let forfeited = if self.attestations.is_empty() {
+            // If no sessions were completed, the entire stake is forfeited
+            self.commitment_stake
+        } else {
+            // If not all sessions are completed, forfeit the current per-session value of the rest
+            self.reward_per_session
+                .checked_mul(self.remaining_sessions())
+                .ok_or(ProgramError::ArithmeticOverflow)?
+        };
+        let penalties = (forfeited as u128)
+            .checked_mul(self.penalty_bps as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / Self::FULL_PENALTY_BPS as u128;
+        let penalties = penalties as u64;
+        let rewards = self
             .commitment_stake
             .checked_sub(penalties)
             .ok_or(ProgramError::ArithmeticOverflow)?;
 
-        Ok(())
+        Ok((rewards, penalties))
+    }
+
+    /// Splits the vault balance at settlement and returns the owner's payout and the yield left for
+    /// the protocol.
+    ///
+    /// The principal (`commitment_stake`) settles as rewards and penalties, the owner's share of the
+    /// yield is always paid out - even when sessions were missed - and anything else in the vault is
+    /// an external deposit returned to the owner.
+    ///
+    /// A vault holding less than the principal and yield, e.g. after a lending market returned less
+    /// than was lent, settles for what it holds. The shortfall comes out of the protocol's yield,
+    /// then the penalties, then the owner's yield and only then the rewards, and is recorded on the
+    /// plan.
+    pub fn settle_vault(&mut self, vault_balance: u64, owner_yield: u64) -> Result<(u64, u64)> {
+        let principal_and_yield = self
+            .commitment_stake
+            .checked_add(self.accrued_yield)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        let mut owner_yield = owner_yield;
+        let mut protocol_yield = self
+            .accrued_yield
+            .checked_sub(owner_yield)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        match vault_balance.checked_sub(principal_and_yield) {
+            Some(external_deposits) => self.external_deposits = external_deposits,
+            None => {
+                self.external_deposits = 0;
+                self.shortfall = principal_and_yield - vault_balance;
+                let mut uncovered = self.shortfall;
+                for share in [
+                    &mut protocol_yield,
+                    &mut self.penalties,
+                    &mut owner_yield,
+                    &mut self.rewards,
+                ] {
+                    let covered = uncovered.min(*share);
+                    *share -= covered;
+                    uncovered -= covered;
+                }
+                self.accrued_yield = owner_yield + protocol_yield;
+            }
+        }
+
+        let payout = self
+            .rewards
+            .checked_add(owner_yield)
+            .and_then(|payout| payout.checked_add(self.external_deposits))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok((payout, protocol_yield))
+    }
+
+    /// Tokens the treasury collects from the vault at settlement - the protocol's yield and the
+    /// penalties of plans that don't donate them, so nothing is left in the vault once the payout
+    /// and any donation are out
+    pub fn treasury_share(&self, protocol_yield: u64) -> Result<u64> {
+        let penalties = if self.has_charity() {
+            0
+        } else {
+            self.penalties
+        };
+        penalties
+            .checked_add(protocol_yield)
+            .ok_or(ProgramError::ArithmeticOverflow.into())
     }
 
     pub fn save_attestation(
@@ -69,44 +385,331 @@ impl MeditationPlan {
         attester: Pubkey,
         started_at: i64,
         ended_at: i64,
+        now: i64,
     ) -> Result<()> {
-        self.validate_attestation(attester, started_at, ended_at)?;
+        // Self-attestations must land before the plan ends - later ones go through the dispute window
+        self.require_active(now)?;
+        self.validate_attestation(attester, started_at, ended_at, now)?;
+        self.record_attestation(attester, started_at, ended_at)
+    }
 
+    /// Records a session that failed to land on-chain before the plan ended, vouched for by the
+    /// attestation oracle. Only accepted while the plan is pending settlement.
+    pub fn save_late_attestation(
+        &mut self,
+        oracle: Pubkey,
+        started_at: i64,
+        ended_at: i64,
+        now: i64,
+    ) -> Result<()> {
+        self.transition(PlanStatus::PendingSettlement, now)?;
+        self.validate_session(started_at, ended_at, now)?;
+        self.record_attestation(oracle, started_at, ended_at)
+    }
+
+    fn record_attestation(
+        &mut self,
+        attester: Pubkey,
+        started_at: i64,
+        ended_at: i64,
+    ) -> Result<()> {
         self.attestations.push(MeditationAttestation {
             attester,
             started_at,
             ended_at,
         });
 
-        let total_sessions = self.total_sessions();
-        let reward_per_session = self.reward_per_session(total_sessions);
         self.rewards = self
             .rewards
-            .checked_add(reward_per_session)
+            .checked_add(self.reward_per_session)
             .ok_or(ProgramError::ArithmeticOverflow)?;
 
         Ok(())
     }
 
-    fn reward_per_session(&self, total_sessions: u64) -> u64 {
-        self.commitment_stake
-            .checked_div(total_sessions)
-            .ok_or(ProgramError::ArithmeticOverflow)
-            .unwrap()
+    /// Adds to the stake mid-plan. Sessions already attested keep the value they were credited at
+    /// and the rest of the stake is spread over the remaining sessions.
+    pub fn increase_stake(&mut self, amount: u64, mint_decimals: u8, now: i64) -> Result<()> {
+        self.require_active(now)?;
+        require_gt!(
+            self.remaining_sessions(),
+            0,
+            MeditationPlanError::NoRemainingSessions
+        );
+        require_gt!(amount, 0, MeditationPlanError::InvalidCommitmentStakeAmount);
+
+        let commitment_stake = self
+            .commitment_stake
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Self::validate_commitment_stake(commitment_stake, mint_decimals)?;
+
+        self.commitment_stake = commitment_stake;
+        self.rebalance_reward_per_session()
     }
 
-    fn total_sessions(&self) -> u64 {
+    /// Adds days to a running plan and spreads the stake not yet credited over the longer plan.
+    ///
+    /// The account is sized for the longest plan at the highest frequency, so attestations for the
+    /// extra days always fit without a realloc.
+    pub fn extend(&mut self, additional_days: u8, now: i64) -> Result<()> {
+        self.require_active(now)?;
+        require_gt!(additional_days, 0, MeditationPlanError::InvalidNumberOfDays);
+
+        let number_of_days = self
+            .number_of_days
+            .checked_add(additional_days)
+            .ok_or(MeditationPlanError::InvalidNumberOfDays)?;
+        require_gte!(30, number_of_days, MeditationPlanError::InvalidNumberOfDays);
+
+        self.number_of_days = number_of_days;
+        self.end_at = self
+            .end_at
+            .checked_add(additional_days as i64 * DAY_IN_SECONDS)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.rebalance_reward_per_session()
+    }
+
+    /// Spreads the stake not yet credited evenly over the sessions still to be attested
+    fn rebalance_reward_per_session(&mut self) -> Result<()> {
+        let unearned = self
+            .commitment_stake
+            .checked_sub(self.rewards)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.reward_per_session = unearned.checked_div(self.remaining_sessions()).unwrap_or(0);
+        Ok(())
+    }
+
+    /// Freezes the plan so attestations are rejected until it is resumed
+    pub fn pause(&mut self, now: i64) -> Result<()> {
+        self.transition(PlanStatus::Paused, now)?;
+        require_gt!(
+            MAX_PAUSE_SECONDS,
+            self.paused_seconds,
+            MeditationPlanError::PauseLimitExceeded
+        );
+        self.paused_at = now;
+
+        Ok(())
+    }
+
+    /// Reactivates the plan and pushes `end_at` back by the paused time, up to the remaining
+    /// pause allowance
+    pub fn resume(&mut self, now: i64) -> Result<()> {
+        let was_paused = self.is_paused();
+        self.transition(PlanStatus::Active, now)?;
+        require!(was_paused, MeditationPlanError::PlanNotPaused);
+
+        let paused_seconds = now
+            .checked_sub(self.paused_at)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            .min(MAX_PAUSE_SECONDS - self.paused_seconds);
+        self.end_at = self
+            .end_at
+            .checked_add(paused_seconds)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.paused_seconds += paused_seconds;
+        self.paused_at = 0;
+
+        Ok(())
+    }
+
+    /// Withdraws a plan before it starts - nothing has been attested, so the whole vault is
+    /// refunded
+    pub fn cancel(&mut self, now: i64) -> Result<()> {
+        self.transition(PlanStatus::Cancelled, now)
+    }
+
+    /// Moves a completed or cancelled plan to `Closed` before its accounts are closed - not named
+    /// `close` as that would be shadowed by `Account::close`
+    pub fn retire(&mut self, now: i64) -> Result<()> {
+        self.transition(PlanStatus::Closed, now)
+    }
+
+    /// Moves the plan to `to` - the one place its lifecycle changes, rejecting illegal moves with
+    /// the error that explains them. Moving to the current status checks the plan can stay there,
+    /// e.g. attestations keep a plan `Active` and late attestations keep it `PendingSettlement`.
+    pub fn transition(&mut self, to: PlanStatus, now: i64) -> Result<()> {
+        use PlanStatus::*;
+
+        require!(
+            self.is_current_layout(),
+            MeditationPlanError::PlanNotMigrated
+        );
+        let from = self.status_at(now);
+        match (from, to) {
+            (Scheduled, Cancelled) | (Completed, Closed) | (Cancelled, Closed) => {}
+            (Scheduled, _) => return err!(MeditationPlanError::PlanNotStarted),
+            (Active, Active) | (Active, Paused) | (Paused, Active) => {}
+            // Only plans with every session attested can settle before they end
+            (Active, Completed) => require!(
+                self.all_sessions_completed(),
+                MeditationPlanError::PlanNotEnded
+            ),
+            (Active, PendingSettlement) => return err!(MeditationPlanError::DisputeWindowClosed),
+            // A plan left paused can only be settled once resuming could no longer extend it
+            (Paused, Completed) => require_gt!(
+                now,
+                self.settlement_deadline(),
+                MeditationPlanError::PlanInactive
+            ),
+            (Paused, _) => return err!(MeditationPlanError::PlanInactive),
+            (PendingSettlement, PendingSettlement) => require_gte!(
+                self.dispute_window_ends_at(),
+                now,
+                MeditationPlanError::DisputeWindowClosed
+            ),
+            // Missed sessions can only be penalized once the dispute window has closed
+            (PendingSettlement, Completed) => require!(
+                self.all_sessions_completed() || now > self.dispute_window_ends_at(),
+                MeditationPlanError::DisputeWindowActive
+            ),
+            (PendingSettlement, Active) | (PendingSettlement, Paused) => {
+                return err!(MeditationPlanError::PlanExpired)
+            }
+            (Completed, _) | (Closed, _) => return err!(MeditationPlanError::PlanCompleted),
+            (Cancelled, _) => return err!(MeditationPlanError::PlanInactive),
+            _ => return err!(MeditationPlanError::InvalidStatusTransition),
+        }
+
+        self.status = to;
+        Ok(())
+    }
+
+    /// Keeps a running plan `Active` - unlike `transition`, a paused plan isn't resumed
+    fn require_active(&mut self, now: i64) -> Result<()> {
+        require!(!self.is_paused(), MeditationPlanError::PlanInactive);
+        self.transition(PlanStatus::Active, now)
+    }
+
+    /// The stored status with the moves driven by time applied: a scheduled plan is active from
+    /// `start_at`, and a running plan is pending settlement after `end_at`
+    pub fn status_at(&self, now: i64) -> PlanStatus {
+        match self.status {
+            PlanStatus::Scheduled | PlanStatus::Active if now > self.end_at => {
+                PlanStatus::PendingSettlement
+            }
+            PlanStatus::Scheduled if now >= self.start_at => PlanStatus::Active,
+            status => status,
+        }
+    }
+
+    /// When anyone may settle the plan: the grace period after `end_at`, plus whatever pause
+    /// allowance is left if the plan is paused, as resuming would push `end_at` back that far
+    pub fn settlement_deadline(&self) -> i64 {
+        let remaining_pause = if self.is_paused() {
+            MAX_PAUSE_SECONDS.saturating_sub(self.paused_seconds)
+        } else {
+            0
+        };
+        self.end_at
+            .saturating_add(SETTLEMENT_GRACE_SECONDS)
+            .saturating_add(remaining_pause)
+    }
+
+    /// End of the window after `end_at` during which late attestations are accepted and
+    /// penalties can't be settled yet
+    pub fn dispute_window_ends_at(&self) -> i64 {
+        self.end_at.saturating_add(DISPUTE_WINDOW_SECONDS)
+    }
+
+    pub fn is_pending_settlement(&self, now: i64) -> bool {
+        self.status_at(now) == PlanStatus::PendingSettlement && now <= self.dispute_window_ends_at()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.status == PlanStatus::Paused
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.status == PlanStatus::Completed
+    }
+
+    pub fn is_current_layout(&self) -> bool {
+        self.version == Self::LAYOUT_VERSION
+    }
+
+    pub fn is_lending(&self) -> bool {
+        self.lending_program != Pubkey::default()
+    }
+
+    pub fn has_charity(&self) -> bool {
+        self.charity != Pubkey::default()
+    }
+
+    pub fn all_sessions_completed(&self) -> bool {
+        self.sessions_completed() >= self.total_sessions()
+    }
+
+    /// Share of planned sessions that were attested, in basis points
+    pub fn completion_ratio_bps(&self) -> u64 {
+        let sessions_completed = self.sessions_completed().min(self.total_sessions());
+        (sessions_completed * 10_000)
+            .checked_div(self.total_sessions())
+            .unwrap_or(0)
+    }
+
+    pub fn sessions_completed(&self) -> u64 {
+        self.attestations.len() as u64
+    }
+
+    /// Sessions of plan days that are over without an attestation. Days spent paused aren't
+    /// counted, and every remaining session is missed once the plan has ended.
+    pub fn missed_sessions(&self, now: i64) -> u64 {
+        if self.is_completed() || self.status_at(now) == PlanStatus::PendingSettlement {
+            return self.remaining_sessions();
+        }
+        if now < self.start_at {
+            return 0;
+        }
+
+        let day_index = (now - self.start_at) / DAY_IN_SECONDS;
+        let today_start = self.start_at + day_index * DAY_IN_SECONDS;
+        let paused_seconds = if self.is_paused() {
+            self.paused_seconds
+                .saturating_add(now.saturating_sub(self.paused_at).max(0))
+        } else {
+            self.paused_seconds
+        };
+        let days_over = (day_index - paused_seconds / DAY_IN_SECONDS)
+            .clamp(0, self.number_of_days as i64) as u64;
+        let attested_before_today = self
+            .attestations
+            .iter()
+            .filter(|attestation| attestation.started_at < today_start)
+            .count() as u64;
+
+        (days_over * self.daily_frequency as u64).saturating_sub(attested_before_today)
+    }
+
+    pub fn remaining_sessions(&self) -> u64 {
+        self.total_sessions()
+            .saturating_sub(self.sessions_completed())
+    }
+
+    pub fn total_sessions(&self) -> u64 {
         self.number_of_days as u64 * self.daily_frequency as u64
     }
 
-    fn validate_attestation(&self, attester: Pubkey, started_at: i64, ended_at: i64) -> Result<()> {
+    fn validate_attestation(
+        &self,
+        attester: Pubkey,
+        started_at: i64,
+        ended_at: i64,
+        now: i64,
+    ) -> Result<()> {
         // Ensure attester is the owner
         require!(
             self.owner == attester,
             MeditationPlanError::UnauthorizedAccess
         );
 
-        let now = Clock::get()?.unix_timestamp;
+        self.validate_session(started_at, ended_at, now)
+    }
+
+    /// Checks the session fits the plan: in the past, within the plan dates, long enough, within
+    /// the planned sessions and within the daily frequency
+    fn validate_session(&self, started_at: i64, ended_at: i64, now: i64) -> Result<()> {
         // Ensure timestamps are both in the past
         require!(
             started_at <= now && ended_at <= now,
@@ -144,9 +747,19 @@ impl MeditationPlan {
             MeditationPlanError::AttestationTooLong
         );
 
+        // Ensure the plan still has sessions to attest - pauses push `end_at` back, so there can be
+        // more plan days left than sessions
+        require_gt!(
+            self.remaining_sessions(),
+            0,
+            MeditationPlanError::NoRemainingSessions
+        );
+
         // Ensure the daily frequency has not been exceeded
         let start_at = self.start_at;
-        let elapsed_seconds = started_at - start_at;
+        let elapsed_seconds = started_at
+            .checked_sub(start_at)
+            .ok_or(MeditationPlanError::InvalidTimestamps)?;
         let day_index = elapsed_seconds / DAY_IN_SECONDS;
         let day_start = start_at + day_index * DAY_IN_SECONDS;
         let day_end = day_start + DAY_IN_SECONDS;
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{MeditationAttestation, MeditationPlan, PlanStatus};
+
+/// Layout of `MeditationPlan` accounts written by the program before the layout version was added.
+/// Only read by `migrate_plan`, which rewrites them in the current layout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct MeditationPlanV0 {
+    #[max_len(120)]
+    pub attestations: Vec<MeditationAttestation>,
+    pub bump: u8,
+    pub commitment_stake: u64,
+    pub daily_frequency: u8,
+    pub duration_minutes: u8,
+    pub end_at: i64,
+    pub id: u64,
+    pub is_active: bool,
+    pub is_completed: bool,
+    pub number_of_days: u8,
+    pub owner: Pubkey,
+    pub penalties: u64,
+    pub rewards: u64,
+    pub start_at: i64,
+}
+
+impl MeditationPlanV0 {
+    /// Size of a plan account in this layout, discriminator included
+    pub const SPACE: usize = MeditationPlan::DISCRIMINATOR.len() + Self::INIT_SPACE;
+}
+
+impl From<MeditationPlanV0> for MeditationPlan {
+    /// Fields the legacy layout didn't have take the values of a plan created by hand with no
+    /// beneficiary, charity, lending or template. `is_active` was only ever cleared on completion,
+    /// so every plan that isn't completed is active - `status_at` still reports it pending
+    /// settlement once it is past `end_at`.
+    fn from(plan: MeditationPlanV0) -> Self {
+        let mut migrated = MeditationPlan::new(
+            plan.owner,
+            plan.owner,
+            plan.id,
+            plan.bump,
+            plan.number_of_days,
+            plan.daily_frequency,
+            plan.duration_minutes,
+            plan.commitment_stake,
+            plan.start_at,
+            Pubkey::default(),
+        );
+        migrated.attestations = plan.attestations;
+        migrated.end_at = plan.end_at;
+        migrated.penalties = plan.penalties;
+        migrated.rewards = plan.rewards;
+        migrated.status = if plan.is_completed {
+            PlanStatus::Completed
+        } else {
+            PlanStatus::Active
+        };
+        migrated
+    }
+}
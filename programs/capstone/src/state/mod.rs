@@ -1,5 +1,19 @@
+pub mod charity;
+pub mod config;
 pub mod meditation_attestation;
 pub mod meditation_plan;
+pub mod meditation_plan_v0;
+pub mod plan_status;
+pub mod plan_template;
+pub mod practitioner;
+pub mod projected_settlement;
 
+pub use charity::*;
+pub use config::*;
 pub use meditation_attestation::*;
 pub use meditation_plan::*;
+pub use meditation_plan_v0::*;
+pub use plan_status::*;
+pub use plan_template::*;
+pub use practitioner::*;
+pub use projected_settlement::*;
@@ -0,0 +1,20 @@
+use anchor_lang::prelude::*;
+
+/// Lifecycle of a meditation plan - only ever changed through `MeditationPlan::transition`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum PlanStatus {
+    /// Created with a start date still in the future
+    Scheduled,
+    /// Running and accepting attestations from the owner
+    Active,
+    /// Frozen by the owner until it is resumed
+    Paused,
+    /// Past `end_at` and waiting to be settled - late attestations land during the dispute window
+    PendingSettlement,
+    /// Settled, with rewards and penalties final
+    Completed,
+    /// Withdrawn before it started
+    Cancelled,
+    /// Completed or cancelled and no longer used
+    Closed,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::error::MeditationPlanError;
+use crate::state::MeditationPlan;
+
+/// Preset plan parameters published by an admin or creator for one-click plan creation
+#[account]
+#[derive(InitSpace)]
+pub struct PlanTemplate {
+    pub bump: u8,
+    pub creator: Pubkey,
+    pub daily_frequency: u8,
+    pub duration_minutes: u8,
+    pub id: u64,
+    #[max_len(32)]
+    pub name: String,
+    pub number_of_days: u8,
+    /// Share of the value of missed sessions forfeited at settlement, in basis points
+    pub penalty_bps: u16,
+    /// Stake used when the plan creator doesn't choose one
+    pub suggested_stake: u64,
+}
+
+impl PlanTemplate {
+    pub const MAX_NAME_LEN: usize = 32;
+    /// Templates must forfeit at least a quarter of the value of missed sessions - anyone can
+    /// publish one, and a plan that costs nothing to abandon isn't a commitment
+    pub const MIN_PENALTY_BPS: u16 = 2_500;
+
+    pub fn validate(&self, mint_decimals: u8) -> Result<()> {
+        require!(
+            !self.name.is_empty() && self.name.len() <= Self::MAX_NAME_LEN,
+            MeditationPlanError::InvalidTemplateName
+        );
+        require_gte!(
+            MeditationPlan::FULL_PENALTY_BPS,
+            self.penalty_bps,
+            MeditationPlanError::InvalidBasisPoints
+        );
+        require_gte!(
+            self.penalty_bps,
+            Self::MIN_PENALTY_BPS,
+            MeditationPlanError::TemplatePenaltyTooLow
+        );
+
+        MeditationPlan::validate_parameters(
+            self.number_of_days,
+            self.daily_frequency,
+            self.duration_minutes,
+            self.suggested_stake,
+            mint_decimals,
+        )
+    }
+}
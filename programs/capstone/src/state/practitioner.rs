@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::DAY_IN_SECONDS;
+
+/// Lifetime meditation stats for a wallet, aggregated across all of its plans
+#[account]
+#[derive(InitSpace)]
+pub struct Practitioner {
+    pub bump: u8,
+    pub current_streak: u32,
+    /// Day number (unix timestamp / 1 day) of the latest attested session
+    pub last_session_day: i64,
+    pub lifetime_sessions: u64,
+    pub longest_streak: u32,
+    pub owner: Pubkey,
+    pub plans_completed: u32,
+    pub plans_failed: u32,
+    pub reputation_score: u64,
+    pub total_minutes: u64,
+}
+
+impl Practitioner {
+    pub const SESSION_POINTS: u64 = 10;
+    pub const STREAK_DAY_POINTS: u64 = 5;
+    pub const PLAN_COMPLETED_POINTS: u64 = 100;
+    pub const PLAN_FAILED_POINTS: u64 = 50;
+
+    /// Sets the owner and bump the first time the account is created
+    pub fn initialize_if_needed(&mut self, owner: Pubkey, bump: u8) {
+        if self.owner == Pubkey::default() {
+            self.owner = owner;
+            self.bump = bump;
+        }
+    }
+
+    pub fn record_session(&mut self, started_at: i64, ended_at: i64) -> Result<()> {
+        let minutes = (ended_at - started_at) as u64 / 60;
+        self.total_minutes = self
+            .total_minutes
+            .checked_add(minutes)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        // Streaks count consecutive calendar days with at least one session
+        let day = started_at.div_euclid(DAY_IN_SECONDS);
+        if self.lifetime_sessions == 0 || day > self.last_session_day + 1 {
+            self.current_streak = 1;
+            self.last_session_day = day;
+        } else if day == self.last_session_day + 1 {
+            self.current_streak += 1;
+            self.last_session_day = day;
+        }
+        self.longest_streak = self.longest_streak.max(self.current_streak);
+
+        self.lifetime_sessions = self
+            .lifetime_sessions
+            .checked_add(1)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        self.update_reputation_score();
+        Ok(())
+    }
+
+    pub fn record_plan(&mut self, all_sessions_completed: bool) {
+        if all_sessions_completed {
+            self.plans_completed += 1;
+        } else {
+            self.plans_failed += 1;
+        }
+
+        self.update_reputation_score();
+    }
+
+    /// Score = 10 per session + 5 per day of the longest streak + 100 per completed plan
+    ///         - 50 per failed plan (floored at 0)
+    fn update_reputation_score(&mut self) {
+        let earned = self
+            .lifetime_sessions
+            .saturating_mul(Self::SESSION_POINTS)
+            .saturating_add((self.longest_streak as u64).saturating_mul(Self::STREAK_DAY_POINTS))
+            .saturating_add(
+                (self.plans_completed as u64).saturating_mul(Self::PLAN_COMPLETED_POINTS),
+            );
+        let lost = (self.plans_failed as u64).saturating_mul(Self::PLAN_FAILED_POINTS);
+        self.reputation_score = earned.saturating_sub(lost);
+    }
+}
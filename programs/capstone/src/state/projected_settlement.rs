@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+/// What a plan would settle to at a point in time - returned by `preview_settlement`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProjectedSettlement {
+    /// Sessions from plan days that have passed without an attestation
+    pub missed_sessions: u64,
+    /// Part of the stake forfeited - final once the plan is completed
+    pub penalties: u64,
+    /// Sessions still to be attested before the plan is fully completed
+    pub remaining_sessions: u64,
+    /// Part of the stake paid back, excluding yield and external deposits
+    pub rewards: u64,
+}
@@ -1,4 +1,4 @@
-use anchor_lang::{AccountDeserialize, AnchorSerialize, Discriminator};
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, AnchorSerialize, Discriminator, Space};
 use litesvm::LiteSVM;
 use solana_account::Account;
 use solana_clock::Clock;
@@ -11,13 +11,20 @@ use solana_program_option::COption;
 use solana_program_pack::Pack;
 use solana_pubkey::{pubkey, Pubkey};
 use solana_signer::Signer;
+use solana_transaction::Transaction;
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::state::{Account as TokenAccount, AccountState as TokenAccountState};
 use spl_token::ID as TOKEN_PROGRAM_ID;
 use std::cell::Cell;
 use std::str::FromStr;
 
-use crate::MeditationPlan;
+use capstone_client::instructions::{self, PlanParams};
+use capstone_client::pda;
+
+use crate::{
+    Charity, CharityKind, Config, MeditationPlan, PlanTemplate, Practitioner, ProjectedSettlement,
+    DAY_IN_SECONDS,
+};
 
 pub const PROGRAM_ID: &str = "Bvw5aYMCJDM1136hC5GLqmtq1LbsqSKEgC4owCQj9ZYm";
 
@@ -52,6 +59,10 @@ pub struct TestHarness {
     pub bob: Keypair,
     /// Bob's USDC token account
     pub bob_usdc_account: Pubkey,
+    /// Protocol admin's keypair
+    pub admin: Keypair,
+    /// Attestation oracle's keypair, co-signing late attestations
+    pub oracle: Keypair,
 }
 
 impl TestHarness {
@@ -74,13 +85,32 @@ impl TestHarness {
 
         let bob_usdc_account = airdrop_usdc(&mut svm, usdc_mint, bob.pubkey(), 100 * USDC_TOKEN);
 
+        // The config is normally created by the upgrade authority, which LiteSVM programs don't have
+        let admin = Keypair::new();
+        svm.airdrop(&admin.pubkey(), 1_000_000_000).unwrap();
+        let oracle = Keypair::new();
+        set_config(
+            &mut svm,
+            Config {
+                admin: admin.pubkey(),
+                attestation_oracle: oracle.pubkey(),
+                bump: get_config_pda().1,
+                lending_program: Pubkey::default(),
+                owner_yield_bps: 10_000,
+                penalty_fee_bps: 0,
+                yield_fee_bps: 0,
+            },
+        );
+
         (
             svm,
             TestHarness {
+                admin,
                 alice,
                 alice_usdc_account,
                 bob,
                 bob_usdc_account,
+                oracle,
                 program_id,
                 usdc_mint,
             },
@@ -159,7 +189,7 @@ pub fn get_program_id() -> Pubkey {
 }
 
 thread_local! {
-    static ID_COUNTER: Cell<u64> = Cell::new(1);
+    static ID_COUNTER: Cell<u64> = const { Cell::new(1) };
 }
 
 /// Generates a unique ID for testing meditation plans
@@ -174,32 +204,89 @@ pub fn generate_id() -> u64 {
     })
 }
 
-pub fn get_initialize_discriminator() -> Vec<u8> {
-    let discriminator_input = b"global:initialize";
-    anchor_lang::solana_program::hash::hash(discriminator_input).to_bytes()[..8].to_vec()
-}
-
-pub fn get_attest_discriminator() -> Vec<u8> {
-    let discriminator_input = b"global:attest";
-    anchor_lang::solana_program::hash::hash(discriminator_input).to_bytes()[..8].to_vec()
-}
-
-pub fn get_complete_discriminator() -> Vec<u8> {
-    let discriminator_input = b"global:complete";
-    anchor_lang::solana_program::hash::hash(discriminator_input).to_bytes()[..8].to_vec()
-}
-
 pub fn get_meditation_plan(
     svm: &mut LiteSVM,
     meditation_plan: &Pubkey,
 ) -> (Account, MeditationPlan) {
-    let plan_account = svm.get_account(&meditation_plan).unwrap();
+    let plan_account = svm.get_account(meditation_plan).unwrap();
 
     let plan = MeditationPlan::try_deserialize(&mut plan_account.data.as_slice())
         .expect("Anchor deserialize should succeed");
     (plan_account, plan)
 }
 
+pub fn get_practitioner_pda(owner: Pubkey) -> Pubkey {
+    pda::practitioner(&owner).0
+}
+
+pub fn get_practitioner(svm: &mut LiteSVM, owner: Pubkey) -> Practitioner {
+    let practitioner_account = svm.get_account(&get_practitioner_pda(owner)).unwrap();
+
+    Practitioner::try_deserialize(&mut practitioner_account.data.as_slice())
+        .expect("Anchor deserialize should succeed")
+}
+
+pub fn get_plan_template(svm: &mut LiteSVM, plan_template: &Pubkey) -> PlanTemplate {
+    let template_account = svm.get_account(plan_template).unwrap();
+    PlanTemplate::try_deserialize(&mut template_account.data.as_slice())
+        .expect("Anchor deserialize should succeed")
+}
+
+pub fn get_charity_pda(wallet: Pubkey) -> Pubkey {
+    pda::charity(&wallet).0
+}
+
+pub fn get_charity(svm: &mut LiteSVM, charity: &Pubkey) -> Charity {
+    let charity_account = svm.get_account(charity).unwrap();
+    Charity::try_deserialize(&mut charity_account.data.as_slice())
+        .expect("Anchor deserialize should succeed")
+}
+
+/// Wallet of the charity a settlement pays the plan's penalties to - None if the plan has none
+fn get_plan_charity_wallet(svm: &mut LiteSVM, plan: &MeditationPlan) -> Option<Pubkey> {
+    plan.has_charity()
+        .then(|| get_charity(svm, &plan.charity).wallet)
+}
+
+/// The treasury collecting protocol fees is the config PDA's ATA
+pub fn get_treasury_address(mint: Pubkey) -> Pubkey {
+    pda::treasury(&mint)
+}
+
+pub fn get_config_pda() -> (Pubkey, u8) {
+    pda::config()
+}
+
+pub fn get_config(svm: &mut LiteSVM) -> Config {
+    let config_account = svm.get_account(&get_config_pda().0).unwrap();
+
+    Config::try_deserialize(&mut config_account.data.as_slice())
+        .expect("Anchor deserialize should succeed")
+}
+
+/// Writes the config account directly, creating it if needed
+pub fn set_config(svm: &mut LiteSVM, config: Config) {
+    let mut data = Config::DISCRIMINATOR.to_vec();
+    data.extend(
+        config
+            .try_to_vec()
+            .expect("Anchor serialize should succeed"),
+    );
+    let space = Config::DISCRIMINATOR.len() + Config::INIT_SPACE;
+    data.resize(space, 0);
+    svm.set_account(
+        get_config_pda().0,
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(space),
+            data,
+            owner: get_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
 pub fn set_meditation_plan(
     svm: &mut LiteSVM,
     meditation_plan: Pubkey,
@@ -209,7 +296,8 @@ pub fn set_meditation_plan(
 
     let mut data = MeditationPlan::DISCRIMINATOR.to_vec();
     data.extend(plan.try_to_vec().expect("Anchor serialize should succeed"));
-    // plan.try_serialize(&mut data)
+    // Keep the allocated size so the program can write back a plan with more attestations
+    data.resize(data.len().max(plan_account.data.len()), 0);
     svm.set_account(
         meditation_plan,
         Account {
@@ -224,76 +312,111 @@ pub fn set_meditation_plan(
     (plan_account, plan)
 }
 
-pub struct InitializeAccounts {
-    pub associated_token_program: Pubkey,
-    pub meditation_plan: Pubkey,
-    pub mint: Pubkey,
-    pub owner: Pubkey,
-    pub owner_ata: Pubkey,
-    pub system_program: Pubkey,
-    pub token_program: Pubkey,
-    pub vault: Pubkey,
+/// The standard plan as a plain struct starting at `STARTED_AT`, for testing the plan logic
+/// without a transaction
+pub fn standard_plan_fixture() -> MeditationPlan {
+    let owner = Keypair::new().pubkey();
+    MeditationPlan::new(
+        owner,
+        owner,
+        1,
+        255,
+        NUMBER_OF_DAYS,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        COMMITMENT_STAKE,
+        STARTED_AT,
+        Pubkey::default(),
+    )
 }
 
-/// Helper function to create MakeOfferAccounts with standard program IDs
-///
-/// This function eliminates the repetitive initialization of the three standard
-/// program IDs (associated_token_program, token_program, system_program) that
-/// are always the same constants across all tests. Instead of copy-pasting
-/// these three lines in every test, this helper focuses on the variable fields.
-fn build_initialize_accounts(
-    owner: Pubkey,
-    mint: Pubkey,
-    owner_ata: Pubkey,
+/// Records a standard-length session on the fixture `day` days after the plan started
+pub fn attest_fixture_session(plan: &mut MeditationPlan, day: i64) {
+    let started_at = STARTED_AT + day * DAY_IN_SECONDS;
+    let ended_at = started_at + DURATION_MINUTES as i64 * 60;
+    plan.save_attestation(plan.owner, started_at, ended_at, ended_at)
+        .expect("Fixture session should be valid");
+}
+
+/// Size of a plan account written before the layout version was added - attestations for the
+/// longest plan at the highest frequency, then the fixed fields
+pub const LEGACY_PLAN_SPACE: usize =
+    8 + 4 + 120 * (32 + 8 + 8) + 1 + 8 + 1 + 1 + 8 + 8 + 1 + 1 + 1 + 32 + 8 + 8 + 8;
+
+/// The plan written byte by byte in the layout of the program before versioning: attestations,
+/// bump, commitment_stake, daily_frequency, duration_minutes, end_at, id, is_active,
+/// is_completed, number_of_days, owner, penalties, rewards and start_at
+pub fn legacy_plan_data(plan: &MeditationPlan) -> Vec<u8> {
+    let mut data = MeditationPlan::DISCRIMINATOR.to_vec();
+    data.extend((plan.attestations.len() as u32).to_le_bytes());
+    for attestation in &plan.attestations {
+        data.extend(attestation.attester.to_bytes());
+        data.extend(attestation.started_at.to_le_bytes());
+        data.extend(attestation.ended_at.to_le_bytes());
+    }
+    data.push(plan.bump);
+    data.extend(plan.commitment_stake.to_le_bytes());
+    data.push(plan.daily_frequency);
+    data.push(plan.duration_minutes);
+    data.extend(plan.end_at.to_le_bytes());
+    data.extend(plan.id.to_le_bytes());
+    data.push(u8::from(!plan.is_completed()));
+    data.push(u8::from(plan.is_completed()));
+    data.push(plan.number_of_days);
+    data.extend(plan.owner.to_bytes());
+    data.extend(plan.penalties.to_le_bytes());
+    data.extend(plan.rewards.to_le_bytes());
+    data.extend(plan.start_at.to_le_bytes());
+    data.resize(LEGACY_PLAN_SPACE, 0);
+    data
+}
+
+/// Overwrites the plan account with the legacy layout, funded like a plan created back then
+pub fn set_legacy_meditation_plan(
+    svm: &mut LiteSVM,
     meditation_plan: Pubkey,
-    vault: Pubkey,
-) -> InitializeAccounts {
-    InitializeAccounts {
-        associated_token_program: spl_associated_token_account::ID,
-        token_program: spl_token::ID,
-        system_program: anchor_lang::system_program::ID,
-        owner,
-        mint,
-        owner_ata,
+    plan: &MeditationPlan,
+) {
+    let plan_account = svm.get_account(&meditation_plan).unwrap();
+    svm.set_account(
         meditation_plan,
-        vault,
-    }
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(LEGACY_PLAN_SPACE),
+            data: legacy_plan_data(plan),
+            owner: plan_account.owner,
+            executable: plan_account.executable,
+            rent_epoch: plan_account.rent_epoch,
+        },
+    )
+    .unwrap();
 }
 
-fn build_initialize_instruction(
-    id: u64,
+fn plan_params(
     number_of_days: u8,
     daily_frequency: u8,
     duration_minutes: u8,
     commitment_stake: u64,
-    accounts: InitializeAccounts,
-) -> Instruction {
-    let mut instruction_data = get_initialize_discriminator();
-    instruction_data.extend_from_slice(&id.to_le_bytes());
-    instruction_data.extend_from_slice(&number_of_days.to_le_bytes());
-    instruction_data.extend_from_slice(&daily_frequency.to_le_bytes());
-    instruction_data.extend_from_slice(&duration_minutes.to_le_bytes());
-    instruction_data.extend_from_slice(&commitment_stake.to_le_bytes());
-
-    let account_metas = vec![
-        AccountMeta::new(accounts.owner, true),
-        AccountMeta::new(accounts.meditation_plan, false),
-        AccountMeta::new_readonly(accounts.mint, false),
-        AccountMeta::new(accounts.owner_ata, false),
-        AccountMeta::new(accounts.vault, false),
-        AccountMeta::new_readonly(accounts.associated_token_program, false),
-        AccountMeta::new_readonly(accounts.token_program, false),
-        AccountMeta::new_readonly(accounts.system_program, false),
-    ];
-
-    Instruction {
-        program_id: get_program_id(),
-        accounts: account_metas,
-        data: instruction_data,
+) -> PlanParams {
+    PlanParams {
+        number_of_days,
+        daily_frequency,
+        duration_minutes,
+        commitment_stake,
     }
 }
 
+/// The standard plan every test starts from
+fn standard_plan_params() -> PlanParams {
+    plan_params(
+        NUMBER_OF_DAYS,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        COMMITMENT_STAKE,
+    )
+}
+
 /// Initializes a meditation plan and sends USDC to vault
+#[allow(clippy::too_many_arguments)]
 pub fn execute_initialize(
     svm: &mut LiteSVM,
     usdc_mint: Pubkey,
@@ -305,26 +428,23 @@ pub fn execute_initialize(
     duration_minutes: u8,
     commitment_stake: u64,
 ) -> Result<(Pubkey, u8, Pubkey), SolanaKiteError> {
-    // Create PDAs
-    let (meditation_plan, meditation_bump) = get_pda_and_bump(
-        &seeds!["meditation_plan", owner.pubkey(), id],
-        &get_program_id(),
-    );
-    let vault =
-        spl_associated_token_account::get_associated_token_address(&meditation_plan, &usdc_mint);
+    let (meditation_plan, meditation_bump) = pda::meditation_plan(&owner.pubkey(), id);
+    let vault = pda::vault(&meditation_plan, &usdc_mint);
 
-    // Build accounts
-    let initialize_accounts =
-        build_initialize_accounts(owner.pubkey(), usdc_mint, owner_ata, meditation_plan, vault);
-
-    // Build and execute instruction
-    let initialize_instruction = build_initialize_instruction(
+    let initialize_instruction = instructions::initialize(
+        owner.pubkey(),
+        owner_ata,
+        usdc_mint,
         id,
-        number_of_days,
-        daily_frequency,
-        duration_minutes,
-        commitment_stake,
-        initialize_accounts,
+        plan_params(
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            commitment_stake,
+        ),
+        None,
+        None,
+        None,
     );
 
     send_transaction_from_instructions(
@@ -357,40 +477,173 @@ pub fn create_standard_plan(svm: &mut LiteSVM, harness: &TestHarness) -> (Pubkey
     (meditation_plan, vault)
 }
 
-// Attest helpers
-pub struct AttestAccounts {
-    pub attester: Pubkey,
-    pub meditation_plan: Pubkey,
-    pub system_program: Pubkey,
+/// Initializes a standard plan for alice that pays out to the given beneficiary
+pub fn create_plan_with_beneficiary(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    beneficiary: Pubkey,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    execute_initialize_standard_plan(svm, harness, Some(beneficiary), None)
 }
 
-fn build_attest_accounts(attester: Pubkey, meditation_plan: Pubkey) -> AttestAccounts {
-    AttestAccounts {
-        system_program: anchor_lang::system_program::ID,
-        attester,
-        meditation_plan,
+/// Initializes a standard plan for alice that donates its penalties to the given charity
+pub fn create_plan_with_charity(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    charity: Pubkey,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    execute_initialize_standard_plan(svm, harness, None, Some(charity))
+}
+
+fn execute_initialize_standard_plan(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    beneficiary: Option<Pubkey>,
+    charity: Option<Pubkey>,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    let owner = &harness.alice;
+    let id = generate_id();
+    let (meditation_plan, _meditation_bump) = pda::meditation_plan(&owner.pubkey(), id);
+    let vault = pda::vault(&meditation_plan, &harness.usdc_mint);
+
+    let instruction = instructions::initialize(
+        owner.pubkey(),
+        harness.alice_usdc_account,
+        harness.usdc_mint,
+        id,
+        standard_plan_params(),
+        beneficiary,
+        charity,
+        None,
+    );
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())?;
+
+    // Set the clock to be after the ended_at time so a standard attestation is valid by default
+    set_clock(svm, ENDED_AT + 1);
+
+    Ok((meditation_plan, vault))
+}
+
+/// Initializes a standard plan for alice scheduled to start at `start_at`
+pub fn create_scheduled_plan(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    start_at: i64,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    let owner = &harness.alice;
+    let id = generate_id();
+    let (meditation_plan, _meditation_bump) = pda::meditation_plan(&owner.pubkey(), id);
+    let vault = pda::vault(&meditation_plan, &harness.usdc_mint);
+
+    let instruction = instructions::initialize(
+        owner.pubkey(),
+        harness.alice_usdc_account,
+        harness.usdc_mint,
+        id,
+        standard_plan_params(),
+        None,
+        None,
+        Some(start_at),
+    );
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())?;
+
+    Ok((meditation_plan, vault))
+}
+
+/// Parameters of a plan template - mirrors the create_plan_template arguments
+pub struct PlanTemplateParams {
+    pub name: String,
+    pub number_of_days: u8,
+    pub daily_frequency: u8,
+    pub duration_minutes: u8,
+    pub suggested_stake: u64,
+    pub penalty_bps: u16,
+}
+
+impl PlanTemplateParams {
+    /// A "Beginner 7-day" preset matching the standard plan parameters
+    pub fn beginner() -> Self {
+        Self {
+            name: "Beginner 7-day".to_string(),
+            number_of_days: NUMBER_OF_DAYS,
+            daily_frequency: DAILY_FREQUENCY,
+            duration_minutes: DURATION_MINUTES,
+            suggested_stake: COMMITMENT_STAKE,
+            penalty_bps: 5_000,
+        }
     }
 }
 
-fn build_attest_instruction(
-    started_at: i64,
-    ended_at: i64,
-    accounts: AttestAccounts,
-) -> Instruction {
-    let mut instruction_data = get_attest_discriminator();
-    instruction_data.extend_from_slice(&started_at.to_le_bytes());
-    instruction_data.extend_from_slice(&ended_at.to_le_bytes());
-
-    let account_metas = vec![
-        AccountMeta::new(accounts.attester, true),
-        AccountMeta::new(accounts.meditation_plan, false),
-        AccountMeta::new_readonly(accounts.system_program, false),
-    ];
-
-    Instruction {
-        program_id: get_program_id(),
-        accounts: account_metas,
-        data: instruction_data,
+/// Creates a plan template for the creator and returns its PDA
+pub fn execute_create_plan_template(
+    svm: &mut LiteSVM,
+    usdc_mint: Pubkey,
+    creator: &Keypair,
+    id: u64,
+    params: PlanTemplateParams,
+) -> Result<Pubkey, SolanaKiteError> {
+    let (plan_template, _template_bump) = pda::plan_template(&creator.pubkey(), id);
+
+    let instruction = instructions::create_plan_template(
+        creator.pubkey(),
+        usdc_mint,
+        id,
+        params.name,
+        params.number_of_days,
+        params.daily_frequency,
+        params.duration_minutes,
+        params.suggested_stake,
+        params.penalty_bps,
+    );
+
+    send_transaction_from_instructions(svm, vec![instruction], &[creator], &creator.pubkey())?;
+    Ok(plan_template)
+}
+
+/// Initializes a plan for alice from a template and returns the plan and vault
+pub fn execute_initialize_from_template(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    plan_template: Pubkey,
+    commitment_stake: Option<u64>,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    let owner = &harness.alice;
+    let id = generate_id();
+    let (meditation_plan, _meditation_bump) = pda::meditation_plan(&owner.pubkey(), id);
+    let vault = pda::vault(&meditation_plan, &harness.usdc_mint);
+
+    let instruction = instructions::initialize_from_template(
+        owner.pubkey(),
+        harness.alice_usdc_account,
+        harness.usdc_mint,
+        plan_template,
+        id,
+        commitment_stake,
+        None,
+        None,
+    );
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())?;
+
+    // Set the clock to be after the ended_at time so a standard attestation is valid by default
+    set_clock(svm, ENDED_AT + 1);
+
+    Ok((meditation_plan, vault))
+}
+
+/// Attests a standard session on the given day of the plan
+pub fn attest_session(svm: &mut LiteSVM, owner: &Keypair, meditation_plan: Pubkey, day: i64) {
+    let started_at = STARTED_AT + (day * DAY_IN_SECONDS);
+    let ended_at = started_at + (DURATION_MINUTES as i64 * 60);
+    set_clock(svm, ended_at + 1); // Set clock so attestation is in the past
+
+    execute_attest(svm, owner, meditation_plan, started_at, ended_at)
+        .expect("Attestation should succeed");
+}
+
+/// Attests one session per day for every day of a standard plan
+pub fn attest_all_sessions(svm: &mut LiteSVM, owner: &Keypair, meditation_plan: Pubkey) {
+    for day in 0..NUMBER_OF_DAYS {
+        attest_session(svm, owner, meditation_plan, day as i64);
     }
 }
 
@@ -401,73 +654,484 @@ pub fn execute_attest(
     started_at: i64,
     ended_at: i64,
 ) -> Result<(), SolanaKiteError> {
-    let accounts = build_attest_accounts(attester.pubkey(), meditation_plan);
-    let instruction = build_attest_instruction(started_at, ended_at, accounts);
+    let (_account, plan) = get_meditation_plan(svm, &meditation_plan);
+    let instruction = instructions::attest(
+        attester.pubkey(),
+        plan.owner,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
     send_transaction_from_instructions(svm, vec![instruction], &[attester], &attester.pubkey())
 }
 
-// Complete helpers
-pub struct CompleteAccounts {
-    pub associated_token_program: Pubkey,
-    pub meditation_plan: Pubkey,
-    pub mint: Pubkey,
-    pub owner: Pubkey,
-    pub owner_ata: Pubkey,
-    pub system_program: Pubkey,
-    pub token_program: Pubkey,
-    pub vault: Pubkey,
+/// Attests a session during the dispute window, co-signed by the oracle
+pub fn execute_submit_late_attestation(
+    svm: &mut LiteSVM,
+    owner: &Keypair,
+    oracle: &Keypair,
+    meditation_plan: Pubkey,
+    started_at: i64,
+    ended_at: i64,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::submit_late_attestation(
+        owner.pubkey(),
+        oracle.pubkey(),
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    send_transaction_from_instructions(svm, vec![instruction], &[owner, oracle], &owner.pubkey())
 }
 
-fn build_complete_accounts(
-    owner: Pubkey,
-    mint: Pubkey,
+pub fn execute_complete(
+    svm: &mut LiteSVM,
+    usdc_mint: Pubkey,
+    owner: &Keypair,
+    beneficiary_ata: Pubkey,
+    meditation_plan: Pubkey,
+    _vault: Pubkey,
+) -> Result<(), SolanaKiteError> {
+    let (_account, plan) = get_meditation_plan(svm, &meditation_plan);
+    let instruction = instructions::complete(
+        owner.pubkey(),
+        usdc_mint,
+        meditation_plan,
+        plan.beneficiary,
+        beneficiary_ata,
+        get_plan_charity_wallet(svm, &plan),
+    );
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
+}
+
+pub fn execute_increase_stake(
+    svm: &mut LiteSVM,
+    usdc_mint: Pubkey,
+    owner: &Keypair,
     owner_ata: Pubkey,
     meditation_plan: Pubkey,
-    vault: Pubkey,
-) -> CompleteAccounts {
-    CompleteAccounts {
-        associated_token_program: spl_associated_token_account::ID,
-        token_program: spl_token::ID,
-        system_program: anchor_lang::system_program::ID,
-        owner,
-        mint,
+    _vault: Pubkey,
+    amount: u64,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::increase_stake(
+        owner.pubkey(),
         owner_ata,
+        usdc_mint,
         meditation_plan,
-        vault,
-    }
+        amount,
+    );
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
 }
 
-fn build_complete_instruction(accounts: CompleteAccounts) -> Instruction {
-    let instruction_data = get_complete_discriminator();
+pub fn execute_pause_plan(
+    svm: &mut LiteSVM,
+    owner: &Keypair,
+    meditation_plan: Pubkey,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::pause_plan(owner.pubkey(), meditation_plan);
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
+}
 
-    let account_metas = vec![
-        AccountMeta::new(accounts.owner, true),
-        AccountMeta::new(accounts.meditation_plan, false),
-        AccountMeta::new_readonly(accounts.mint, false),
-        AccountMeta::new(accounts.owner_ata, false),
-        AccountMeta::new(accounts.vault, false),
-        AccountMeta::new_readonly(accounts.associated_token_program, false),
-        AccountMeta::new_readonly(accounts.token_program, false),
-        AccountMeta::new_readonly(accounts.system_program, false),
-    ];
+pub fn execute_resume_plan(
+    svm: &mut LiteSVM,
+    owner: &Keypair,
+    meditation_plan: Pubkey,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::resume_plan(owner.pubkey(), meditation_plan);
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
+}
 
-    Instruction {
-        program_id: get_program_id(),
-        accounts: account_metas,
-        data: instruction_data,
-    }
+pub fn execute_cancel_plan(
+    svm: &mut LiteSVM,
+    usdc_mint: Pubkey,
+    owner: &Keypair,
+    meditation_plan: Pubkey,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::cancel_plan(owner.pubkey(), usdc_mint, meditation_plan);
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
 }
 
-pub fn execute_complete(
+pub fn execute_close_plan(
+    svm: &mut LiteSVM,
+    usdc_mint: Pubkey,
+    owner: &Keypair,
+    meditation_plan: Pubkey,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::close_plan(owner.pubkey(), usdc_mint, meditation_plan);
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
+}
+
+pub fn execute_migrate_plan(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    meditation_plan: Pubkey,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::migrate_plan(payer.pubkey(), meditation_plan);
+    send_transaction_from_instructions(svm, vec![instruction], &[payer], &payer.pubkey())
+}
+
+pub fn execute_extend_plan(
+    svm: &mut LiteSVM,
+    owner: &Keypair,
+    meditation_plan: Pubkey,
+    additional_days: u8,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::extend_plan(owner.pubkey(), meditation_plan, additional_days);
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
+}
+
+/// Completes a plan and rolls its payout into a new plan, returning the new plan and vault
+#[allow(clippy::too_many_arguments)]
+pub fn execute_renew(
     svm: &mut LiteSVM,
     usdc_mint: Pubkey,
     owner: &Keypair,
     owner_ata: Pubkey,
     meditation_plan: Pubkey,
-    vault: Pubkey,
+    _vault: Pubkey,
+    new_id: u64,
+    number_of_days: u8,
+    daily_frequency: u8,
+    duration_minutes: u8,
+    commitment_stake: u64,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    let (new_meditation_plan, _bump) = pda::meditation_plan(&owner.pubkey(), new_id);
+    let new_vault = pda::vault(&new_meditation_plan, &usdc_mint);
+
+    let (_account, plan) = get_meditation_plan(svm, &meditation_plan);
+    let instruction = instructions::renew(
+        owner.pubkey(),
+        owner_ata,
+        usdc_mint,
+        meditation_plan,
+        plan.beneficiary,
+        new_id,
+        plan_params(
+            number_of_days,
+            daily_frequency,
+            duration_minutes,
+            commitment_stake,
+        ),
+        get_plan_charity_wallet(svm, &plan),
+    );
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())?;
+
+    Ok((new_meditation_plan, new_vault))
+}
+
+/// Settles an expired plan on behalf of its owner, paid for by the keeper
+pub fn execute_settle_expired(
+    svm: &mut LiteSVM,
+    usdc_mint: Pubkey,
+    keeper: &Keypair,
+    owner: Pubkey,
+    meditation_plan: Pubkey,
+    _vault: Pubkey,
+) -> Result<(), SolanaKiteError> {
+    let (_account, plan) = get_meditation_plan(svm, &meditation_plan);
+    let instruction = instructions::settle_expired(
+        keeper.pubkey(),
+        owner,
+        usdc_mint,
+        meditation_plan,
+        plan.beneficiary,
+        get_plan_charity_wallet(svm, &plan),
+    );
+    send_transaction_from_instructions(svm, vec![instruction], &[keeper], &keeper.pubkey())
+}
+
+/// Withdraws protocol fees from the treasury to the given token account
+pub fn execute_withdraw_treasury(
+    svm: &mut LiteSVM,
+    usdc_mint: Pubkey,
+    admin: &Keypair,
+    destination: Pubkey,
+    amount: u64,
+) -> Result<(), SolanaKiteError> {
+    let instruction =
+        instructions::withdraw_treasury(admin.pubkey(), usdc_mint, destination, amount);
+    send_transaction_from_instructions(svm, vec![instruction], &[admin], &admin.pubkey())
+}
+
+/// Registers the wallet as a charity and returns the charity PDA
+pub fn execute_register_charity(
+    svm: &mut LiteSVM,
+    usdc_mint: Pubkey,
+    admin: &Keypair,
+    wallet: Pubkey,
+    name: &str,
+    kind: CharityKind,
+) -> Result<Pubkey, SolanaKiteError> {
+    // The client builds against its own copy of the program types
+    let kind = match kind {
+        CharityKind::Charity => capstone_client::CharityKind::Charity,
+        CharityKind::AntiCharity => capstone_client::CharityKind::AntiCharity,
+    };
+    let instruction =
+        instructions::register_charity(admin.pubkey(), wallet, usdc_mint, name.to_string(), kind);
+    send_transaction_from_instructions(svm, vec![instruction], &[admin], &admin.pubkey())?;
+    Ok(get_charity_pda(wallet))
+}
+
+/// Activates or retires a registered charity
+pub fn execute_update_charity(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    charity: Pubkey,
+    is_active: bool,
+) -> Result<(), SolanaKiteError> {
+    let instruction = instructions::update_charity(admin.pubkey(), charity, is_active);
+    send_transaction_from_instructions(svm, vec![instruction], &[admin], &admin.pubkey())
+}
+
+/// Sends preview_settlement and decodes the settlement from the transaction's return data
+pub fn execute_preview_settlement(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    meditation_plan: Pubkey,
+) -> Result<ProjectedSettlement, SolanaKiteError> {
+    let instruction = instructions::preview_settlement(meditation_plan);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    );
+    let metadata = svm
+        .send_transaction(transaction)
+        .map_err(|error| SolanaKiteError::TransactionFailed(format!("{:?}", error)))?;
+    Ok(
+        ProjectedSettlement::try_from_slice(&metadata.return_data.data)
+            .expect("Anchor deserialize should succeed"),
+    )
+}
+
+/// Sends a single instruction and returns the compute units it consumed
+pub fn execute_measured(
+    svm: &mut LiteSVM,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<u64, SolanaKiteError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&signers[0].pubkey()),
+        signers,
+        svm.latest_blockhash(),
+    );
+    let metadata = svm
+        .send_transaction(transaction)
+        .map_err(|error| SolanaKiteError::TransactionFailed(format!("{:?}", error)))?;
+    Ok(metadata.compute_units_consumed)
+}
+
+pub fn get_badge_mint(meditation_plan: Pubkey) -> Pubkey {
+    pda::badge_mint(&meditation_plan).0
+}
+
+/// Mints the completion badge and returns the badge mint and the owner's badge account
+pub fn execute_mint_badge(
+    svm: &mut LiteSVM,
+    owner: &Keypair,
+    meditation_plan: Pubkey,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    let badge_mint = get_badge_mint(meditation_plan);
+    let owner_badge_ata = pda::badge_account(&owner.pubkey(), &badge_mint);
+    let instruction = instructions::mint_badge(owner.pubkey(), meditation_plan);
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())?;
+    Ok((badge_mint, owner_badge_ata))
+}
+
+// Lending helpers
+pub const MOCK_LENDING_RATE_BPS: u16 = 1_000; // 10% simple annual interest
+pub const MOCK_LENDING_LIQUIDITY: u64 = 1_000 * USDC_TOKEN;
+
+/// Accounts for a mock lending pool holding USDC
+pub struct LendingFixture {
+    pub program_id: Pubkey,
+    pub mint: Pubkey,
+    pub reserve: Pubkey,
+}
+
+impl LendingFixture {
+    /// Remaining accounts expected by the mock lending adapter
+    pub fn account_metas(&self, meditation_plan: Pubkey) -> Vec<AccountMeta> {
+        instructions::lending_accounts(self.program_id, self.mint, meditation_plan)
+    }
+}
+
+/// Interest the mock lending pool pays on a single deposit
+pub fn expected_mock_lending_yield(principal: u64, elapsed_seconds: i64) -> u64 {
+    (principal as u128 * MOCK_LENDING_RATE_BPS as u128 * elapsed_seconds as u128
+        / (mock_lending::YEAR_IN_SECONDS as u128 * 10_000)) as u64
+}
+
+/// Deploys the mock lending program, opens a funded USDC pool and approves it in the config
+#[allow(clippy::useless_vec)]
+pub fn setup_mock_lending(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    owner_yield_bps: u16,
+) -> LendingFixture {
+    let program_id = mock_lending::ID;
+    deploy_program(svm, &program_id, "../../target/deploy/mock_lending.so").unwrap();
+
+    let (pool, _bump) = get_pda_and_bump(&seeds!["pool", harness.usdc_mint], &program_id);
+    let reserve = get_associated_token_address(&pool, &harness.usdc_mint);
+
+    let discriminator_input = b"global:initialize_pool";
+    let mut instruction_data =
+        anchor_lang::solana_program::hash::hash(discriminator_input).to_bytes()[..8].to_vec();
+    instruction_data.extend_from_slice(&MOCK_LENDING_RATE_BPS.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(harness.admin.pubkey(), true),
+            AccountMeta::new(pool, false),
+            AccountMeta::new_readonly(harness.usdc_mint, false),
+            AccountMeta::new(reserve, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(anchor_lang::system_program::ID, false),
+        ],
+        data: instruction_data,
+    };
+    send_transaction_from_instructions(
+        svm,
+        vec![instruction],
+        &[&harness.admin],
+        &harness.admin.pubkey(),
+    )
+    .expect("Mock lending pool should initialize");
+
+    // Fund the reserve so it can pay out interest
+    airdrop_usdc(svm, harness.usdc_mint, pool, MOCK_LENDING_LIQUIDITY);
+
+    let config = get_config(svm);
+    set_config(
+        svm,
+        Config {
+            lending_program: program_id,
+            owner_yield_bps,
+            ..config
+        },
+    );
+
+    LendingFixture {
+        program_id,
+        mint: harness.usdc_mint,
+        reserve,
+    }
+}
+
+/// Initializes a standard plan whose vault deposits into the mock lending pool
+pub fn execute_initialize_with_lending(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    lending: &LendingFixture,
+    id: u64,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    let owner = &harness.alice;
+    let (meditation_plan, _meditation_bump) = pda::meditation_plan(&owner.pubkey(), id);
+    let vault = pda::vault(&meditation_plan, &harness.usdc_mint);
+
+    let mut instruction = instructions::initialize(
+        owner.pubkey(),
+        harness.alice_usdc_account,
+        harness.usdc_mint,
+        id,
+        standard_plan_params(),
+        None,
+        None,
+        None,
+    );
+    instruction
+        .accounts
+        .extend(lending.account_metas(meditation_plan));
+
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())?;
+    Ok((meditation_plan, vault))
+}
+
+/// Completes a plan whose vault deposited into the mock lending pool
+pub fn execute_complete_with_lending(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    lending: &LendingFixture,
+    meditation_plan: Pubkey,
+    _vault: Pubkey,
 ) -> Result<(), SolanaKiteError> {
-    let accounts =
-        build_complete_accounts(owner.pubkey(), usdc_mint, owner_ata, meditation_plan, vault);
-    let instruction = build_complete_instruction(accounts);
+    let owner = &harness.alice;
+    let mut instruction = instructions::complete(
+        owner.pubkey(),
+        harness.usdc_mint,
+        meditation_plan,
+        owner.pubkey(),
+        harness.alice_usdc_account,
+        None,
+    );
+    instruction
+        .accounts
+        .extend(lending.account_metas(meditation_plan));
+
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
+}
+
+/// Renews a plan whose vault deposited into the mock lending pool into a standard plan that keeps
+/// lending, returning the new plan and vault
+pub fn execute_renew_with_lending(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    lending: &LendingFixture,
+    meditation_plan: Pubkey,
+    _vault: Pubkey,
+    new_id: u64,
+) -> Result<(Pubkey, Pubkey), SolanaKiteError> {
+    let owner = &harness.alice;
+    let (new_meditation_plan, _bump) = pda::meditation_plan(&owner.pubkey(), new_id);
+    let new_vault = pda::vault(&new_meditation_plan, &harness.usdc_mint);
+
+    let mut instruction = instructions::renew(
+        owner.pubkey(),
+        harness.alice_usdc_account,
+        harness.usdc_mint,
+        meditation_plan,
+        owner.pubkey(),
+        new_id,
+        standard_plan_params(),
+        None,
+    );
+    instruction
+        .accounts
+        .extend(lending.account_metas(meditation_plan));
+    instruction
+        .accounts
+        .extend(lending.account_metas(new_meditation_plan));
+
+    send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())?;
+    Ok((new_meditation_plan, new_vault))
+}
+
+/// Tops up a plan whose vault deposited into the mock lending pool
+pub fn execute_increase_stake_with_lending(
+    svm: &mut LiteSVM,
+    harness: &TestHarness,
+    lending: &LendingFixture,
+    meditation_plan: Pubkey,
+    _vault: Pubkey,
+    amount: u64,
+) -> Result<(), SolanaKiteError> {
+    let owner = &harness.alice;
+    let mut instruction = instructions::increase_stake(
+        owner.pubkey(),
+        harness.alice_usdc_account,
+        harness.usdc_mint,
+        meditation_plan,
+        amount,
+    );
+    instruction
+        .accounts
+        .extend(lending.account_metas(meditation_plan));
+
     send_transaction_from_instructions(svm, vec![instruction], &[owner], &owner.pubkey())
 }
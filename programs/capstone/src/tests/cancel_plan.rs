@@ -0,0 +1,200 @@
+use solana_kite::{get_token_account_balance, send_transaction_from_instructions};
+use solana_signer::Signer;
+
+use capstone_client::{instructions, pda};
+
+use crate::test_helpers::{
+    create_scheduled_plan, create_standard_plan, execute_attest, execute_cancel_plan, generate_id,
+    get_meditation_plan, set_clock, setup_mock_lending, TestHarness, COMMITMENT_STAKE,
+    DAILY_FREQUENCY, DURATION_MINUTES, HUNDY_USDC, NUMBER_OF_DAYS,
+};
+use crate::{PlanStatus, DAY_IN_SECONDS, MAX_SCHEDULE_SECONDS};
+
+const START_AT: i64 = 2 * DAY_IN_SECONDS;
+
+#[test]
+fn test_scheduled_plan_starts_later() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_scheduled_plan(&mut svm, &harness, START_AT).unwrap();
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.status, PlanStatus::Scheduled);
+    assert_eq!(plan.start_at, START_AT);
+    assert_eq!(
+        plan.end_at,
+        START_AT + plan.number_of_days as i64 * DAY_IN_SECONDS
+    );
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+
+    // Sessions before the start are rejected
+    let started_at = DAY_IN_SECONDS;
+    let ended_at = started_at + DURATION_MINUTES as i64 * 60;
+    set_clock(&mut svm, ended_at + 1);
+    let result = execute_attest(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    assert!(result.is_err(), "Attestation should fail before the start");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanNotStarted"),
+        "Incorrect error for scheduled plan"
+    );
+
+    // Once it starts, it runs like any other plan
+    let ended_at = START_AT + DURATION_MINUTES as i64 * 60;
+    set_clock(&mut svm, ended_at + 1);
+    let result = execute_attest(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        START_AT,
+        ended_at,
+    );
+    assert!(result.is_ok(), "Attestation should succeed once started");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.status, PlanStatus::Active);
+}
+
+#[test]
+fn test_schedule_outside_window_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    set_clock(&mut svm, DAY_IN_SECONDS);
+
+    for start_at in [
+        DAY_IN_SECONDS - 1,
+        DAY_IN_SECONDS + MAX_SCHEDULE_SECONDS + 1,
+    ] {
+        let result = create_scheduled_plan(&mut svm, &harness, start_at);
+        assert!(result.is_err(), "Scheduling at {start_at} should fail");
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Error Code: InvalidStartTime"),
+            "Incorrect error for start at {start_at}"
+        );
+    }
+}
+
+#[test]
+fn test_cancel_scheduled_plan_refunds_owner() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_scheduled_plan(&mut svm, &harness, START_AT).unwrap();
+
+    let result = execute_cancel_plan(&mut svm, harness.usdc_mint, &harness.alice, meditation_plan);
+    assert!(result.is_ok(), "Cancelling a scheduled plan should succeed");
+
+    let (plan_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.status, PlanStatus::Cancelled);
+    assert_eq!(
+        plan_account.lamports,
+        svm.minimum_balance_for_rent_exemption(plan_account.data.len()),
+        "The rent reserve should be returned to the owner"
+    );
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC);
+
+    // A cancelled plan never starts
+    let ended_at = START_AT + DURATION_MINUTES as i64 * 60;
+    set_clock(&mut svm, ended_at + 1);
+    let result = execute_attest(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        START_AT,
+        ended_at,
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanInactive"),
+        "Incorrect error for cancelled plan"
+    );
+}
+
+#[test]
+fn test_cancel_scheduled_lending_plan_withdraws_stake() {
+    let (mut svm, harness) = TestHarness::new();
+    let lending = setup_mock_lending(&mut svm, &harness, 10_000);
+    let owner = &harness.alice;
+    let id = generate_id();
+    let meditation_plan = pda::meditation_plan(&owner.pubkey(), id).0;
+
+    let mut instruction = instructions::initialize(
+        owner.pubkey(),
+        harness.alice_usdc_account,
+        harness.usdc_mint,
+        id,
+        instructions::PlanParams {
+            number_of_days: NUMBER_OF_DAYS,
+            daily_frequency: DAILY_FREQUENCY,
+            duration_minutes: DURATION_MINUTES,
+            commitment_stake: COMMITMENT_STAKE,
+        },
+        None,
+        None,
+        Some(START_AT),
+    );
+    instruction
+        .accounts
+        .extend(lending.account_metas(meditation_plan));
+    send_transaction_from_instructions(&mut svm, vec![instruction], &[owner], &owner.pubkey())
+        .expect("Scheduling a lending plan should succeed");
+
+    let mut instruction =
+        instructions::cancel_plan(owner.pubkey(), harness.usdc_mint, meditation_plan);
+    instruction
+        .accounts
+        .extend(lending.account_metas(meditation_plan));
+    send_transaction_from_instructions(&mut svm, vec![instruction], &[owner], &owner.pubkey())
+        .expect("Cancelling a lending plan should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.status, PlanStatus::Cancelled);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC);
+}
+
+#[test]
+fn test_cancel_started_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_cancel_plan(&mut svm, harness.usdc_mint, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Cancelling a started plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidStatusTransition"),
+        "Incorrect error for started plan"
+    );
+}
+
+#[test]
+fn test_unauthorized_cancel_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_scheduled_plan(&mut svm, &harness, START_AT).unwrap();
+
+    let result = execute_cancel_plan(&mut svm, harness.usdc_mint, &harness.bob, meditation_plan);
+    assert!(result.is_err(), "Only the owner can cancel");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAccess"),
+        "Incorrect error for unauthorized cancel"
+    );
+}
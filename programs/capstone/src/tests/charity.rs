@@ -0,0 +1,307 @@
+use solana_account::Account;
+use solana_kite::get_token_account_balance;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::test_helpers::{
+    attest_session, create_plan_with_charity, execute_complete, execute_register_charity,
+    execute_settle_expired, execute_update_charity, get_charity, get_config, get_meditation_plan,
+    get_treasury_address, set_clock, set_config, TestHarness, COMMITMENT_STAKE,
+    REWARDS_PER_SESSION,
+};
+use crate::{CharityKind, Config, SETTLEMENT_GRACE_SECONDS};
+
+#[test]
+fn test_register_charity_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let wallet = Pubkey::new_unique();
+
+    let result = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        wallet,
+        "Mindful Schools",
+        CharityKind::Charity,
+    );
+    assert!(result.is_ok(), "Register charity should succeed");
+
+    let charity = get_charity(&mut svm, &result.unwrap());
+    assert_eq!(charity.wallet, wallet);
+    assert_eq!(charity.name, "Mindful Schools");
+    assert!(charity.is_active);
+    assert_eq!(charity.kind, CharityKind::Charity);
+    assert_eq!(charity.donations, 0);
+    assert_eq!(charity.total_donated, 0);
+
+    let balance = get_token_account_balance(
+        &svm,
+        &get_associated_token_address(&wallet, &harness.usdc_mint),
+    );
+    assert_eq!(balance.unwrap(), 0, "The charity's ATA should be created");
+}
+
+#[test]
+fn test_unauthorized_register_charity_fails() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let result = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice.pubkey(),
+        "Alice's Charity",
+        CharityKind::Charity,
+    );
+    assert!(
+        result.is_err(),
+        "Register charity should fail for non-admin"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAdmin"),
+        "Incorrect error for unauthorized admin"
+    );
+}
+
+#[test]
+fn test_initialize_with_charity_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let charity = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        Pubkey::new_unique(),
+        "Mindful Schools",
+        CharityKind::Charity,
+    )
+    .unwrap();
+
+    let result = create_plan_with_charity(&mut svm, &harness, charity);
+    assert!(result.is_ok(), "Initialize with a charity should succeed");
+
+    let (meditation_plan, _vault) = result.unwrap();
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.charity, charity);
+}
+
+#[test]
+fn test_initialize_with_inactive_charity_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let charity = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        Pubkey::new_unique(),
+        "Mindful Schools",
+        CharityKind::Charity,
+    )
+    .unwrap();
+    execute_update_charity(&mut svm, &harness.admin, charity, false).unwrap();
+
+    let result = create_plan_with_charity(&mut svm, &harness, charity);
+    assert!(
+        result.is_err(),
+        "Initialize with a retired charity should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidCharity"),
+        "Incorrect error for invalid charity"
+    );
+}
+
+#[test]
+fn test_complete_donates_penalties_to_charity() {
+    let (mut svm, harness) = TestHarness::new();
+    // Donated penalties are not charged the protocol fee
+    let config = get_config(&mut svm);
+    set_config(
+        &mut svm,
+        Config {
+            penalty_fee_bps: 1_000,
+            ..config
+        },
+    );
+
+    let wallet = Pubkey::new_unique();
+    let charity = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        wallet,
+        "Mindful Schools",
+        CharityKind::Charity,
+    )
+    .unwrap();
+    let (meditation_plan, vault) = create_plan_with_charity(&mut svm, &harness, charity).unwrap();
+    attest_session(&mut svm, &harness.alice, meditation_plan, 0);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    let expected_donation = COMMITMENT_STAKE - REWARDS_PER_SESSION;
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.penalties, expected_donation);
+    assert_eq!(plan.donated, expected_donation);
+    assert_eq!(plan.protocol_fee, 0);
+
+    let charity = get_charity(&mut svm, &charity);
+    assert_eq!(charity.donations, 1);
+    assert_eq!(charity.total_donated, expected_donation);
+
+    let balance = get_token_account_balance(
+        &svm,
+        &get_associated_token_address(&wallet, &harness.usdc_mint),
+    );
+    assert_eq!(balance.unwrap(), expected_donation);
+
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), 0);
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+}
+
+#[test]
+fn test_retired_charity_still_receives_existing_plan_penalties() {
+    let (mut svm, harness) = TestHarness::new();
+    let wallet = Pubkey::new_unique();
+    let charity = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        wallet,
+        "Mindful Schools",
+        CharityKind::Charity,
+    )
+    .unwrap();
+    let (meditation_plan, vault) = create_plan_with_charity(&mut svm, &harness, charity).unwrap();
+    execute_update_charity(&mut svm, &harness.admin, charity, false).unwrap();
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    let balance = get_token_account_balance(
+        &svm,
+        &get_associated_token_address(&wallet, &harness.usdc_mint),
+    );
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+}
+
+#[test]
+fn test_complete_sends_penalties_to_anti_charity() {
+    let (mut svm, harness) = TestHarness::new();
+    let wallet = Pubkey::new_unique();
+    let anti_charity = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        wallet,
+        "Rival Sports Club",
+        CharityKind::AntiCharity,
+    )
+    .unwrap();
+    assert_eq!(
+        get_charity(&mut svm, &anti_charity).kind,
+        CharityKind::AntiCharity
+    );
+
+    let (meditation_plan, vault) =
+        create_plan_with_charity(&mut svm, &harness, anti_charity).unwrap();
+    attest_session(&mut svm, &harness.alice, meditation_plan, 0);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.charity, anti_charity);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    let expected_penalties = COMMITMENT_STAKE - REWARDS_PER_SESSION;
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.donated, expected_penalties);
+
+    let anti_charity = get_charity(&mut svm, &anti_charity);
+    assert_eq!(anti_charity.donations, 1);
+    assert_eq!(anti_charity.total_donated, expected_penalties);
+
+    let balance = get_token_account_balance(
+        &svm,
+        &get_associated_token_address(&wallet, &harness.usdc_mint),
+    );
+    assert_eq!(balance.unwrap(), expected_penalties);
+}
+
+#[test]
+fn test_settle_expired_recreates_closed_charity_ata() {
+    let (mut svm, harness) = TestHarness::new();
+    let wallet = Pubkey::new_unique();
+    let charity = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        wallet,
+        "Mindful Schools",
+        CharityKind::Charity,
+    )
+    .unwrap();
+    let (meditation_plan, vault) = create_plan_with_charity(&mut svm, &harness, charity).unwrap();
+    attest_session(&mut svm, &harness.alice, meditation_plan, 0);
+
+    // The charity closed its USDC account after registering
+    let charity_ata = get_associated_token_address(&wallet, &harness.usdc_mint);
+    svm.set_account(charity_ata, Account::default()).unwrap();
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS + 1);
+    let result = execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    );
+    assert!(
+        result.is_ok(),
+        "Settlement should recreate the charity's ATA"
+    );
+
+    let expected_donation = COMMITMENT_STAKE - REWARDS_PER_SESSION;
+    let balance = get_token_account_balance(&svm, &charity_ata);
+    assert_eq!(balance.unwrap(), expected_donation);
+    assert_eq!(
+        get_charity(&mut svm, &charity).total_donated,
+        expected_donation
+    );
+}
@@ -0,0 +1,116 @@
+use capstone_client::instructions;
+use solana_keypair::Keypair;
+use solana_kite::{get_token_account_balance, send_transaction_from_instructions};
+use solana_signer::Signer;
+
+use crate::test_helpers::{
+    airdrop_usdc, attest_all_sessions, create_scheduled_plan, create_standard_plan,
+    execute_cancel_plan, execute_close_plan, execute_complete, execute_mint_badge,
+    get_meditation_plan, set_clock, TestHarness, HUNDY_USDC, USDC_TOKEN,
+};
+use crate::DAY_IN_SECONDS;
+
+#[test]
+fn test_close_completed_plan_returns_rent_and_tokens() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1);
+    execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Complete should succeed");
+
+    // Someone sends tokens to the vault after it was settled
+    let late_deposit = 5 * USDC_TOKEN;
+    airdrop_usdc(&mut svm, harness.usdc_mint, meditation_plan, late_deposit);
+
+    let rent = svm.get_balance(&meditation_plan).unwrap() + svm.get_balance(&vault).unwrap();
+    let owner_lamports = svm.get_balance(&harness.alice.pubkey()).unwrap();
+
+    // A separate fee payer, so the owner's balance only reflects the rent returned
+    let fee_payer = Keypair::new();
+    svm.airdrop(&fee_payer.pubkey(), 1_000_000_000).unwrap();
+    let instruction =
+        instructions::close_plan(harness.alice.pubkey(), harness.usdc_mint, meditation_plan);
+    send_transaction_from_instructions(
+        &mut svm,
+        vec![instruction],
+        &[&fee_payer, &harness.alice],
+        &fee_payer.pubkey(),
+    )
+    .expect("Closing a completed plan should succeed");
+
+    assert_eq!(svm.get_balance(&meditation_plan).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&vault).unwrap_or(0), 0);
+    assert_eq!(
+        svm.get_balance(&harness.alice.pubkey()).unwrap(),
+        owner_lamports + rent,
+        "The owner should get back the rent of the plan and its vault"
+    );
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC + late_deposit);
+
+    // The badge can no longer be minted once the plan is closed
+    let result = execute_mint_badge(&mut svm, &harness.alice, meditation_plan);
+    assert!(
+        result.is_err(),
+        "Minting a badge for a closed plan should fail"
+    );
+}
+
+#[test]
+fn test_close_cancelled_plan_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) =
+        create_scheduled_plan(&mut svm, &harness, DAY_IN_SECONDS).unwrap();
+    execute_cancel_plan(&mut svm, harness.usdc_mint, &harness.alice, meditation_plan)
+        .expect("Cancelling a scheduled plan should succeed");
+
+    let result = execute_close_plan(&mut svm, harness.usdc_mint, &harness.alice, meditation_plan);
+    assert!(result.is_ok(), "Closing a cancelled plan should succeed");
+    assert_eq!(svm.get_balance(&meditation_plan).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&vault).unwrap_or(0), 0);
+}
+
+#[test]
+fn test_close_active_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_close_plan(&mut svm, harness.usdc_mint, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Closing an active plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidStatusTransition"),
+        "Incorrect error for active plan"
+    );
+}
+
+#[test]
+fn test_unauthorized_close_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) =
+        create_scheduled_plan(&mut svm, &harness, DAY_IN_SECONDS).unwrap();
+    execute_cancel_plan(&mut svm, harness.usdc_mint, &harness.alice, meditation_plan)
+        .expect("Cancelling a scheduled plan should succeed");
+
+    let result = execute_close_plan(&mut svm, harness.usdc_mint, &harness.bob, meditation_plan);
+    assert!(result.is_err(), "Only the owner can close");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAccess"),
+        "Incorrect error for unauthorized close"
+    );
+}
@@ -1,11 +1,15 @@
+use solana_keypair::Keypair;
 use solana_kite::get_token_account_balance;
+use solana_signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
 
 use crate::test_helpers::{
-    create_standard_plan, execute_attest, execute_complete, get_meditation_plan, set_clock,
-    set_meditation_plan, TestHarness, COMMITMENT_STAKE, DURATION_MINUTES, FIFTY_USDC, HUNDY_USDC,
-    NUMBER_OF_DAYS, REWARDS_PER_SESSION, STARTED_AT,
+    airdrop_usdc, attest_all_sessions, create_plan_with_beneficiary, create_standard_plan,
+    execute_attest, execute_complete, get_meditation_plan, get_practitioner, get_treasury_address,
+    set_clock, set_meditation_plan, TestHarness, COMMITMENT_STAKE, DURATION_MINUTES, FIFTY_USDC,
+    HUNDY_USDC, NUMBER_OF_DAYS, REWARDS_PER_SESSION, STARTED_AT, USDC_TOKEN,
 };
-use crate::{MeditationPlan, DAY_IN_SECONDS};
+use crate::{MeditationPlan, PlanStatus, DAY_IN_SECONDS};
 
 #[test]
 fn test_complete_all_sessions_succeeds() {
@@ -54,8 +58,7 @@ fn test_complete_all_sessions_succeeds() {
 
     let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
     assert_eq!(plan.attestations.len(), NUMBER_OF_DAYS as usize);
-    assert_eq!(plan.is_active, true);
-    assert_eq!(plan.is_completed, false);
+    assert_eq!(plan.status, PlanStatus::Active);
     assert_eq!(plan.penalties, 0);
     assert_eq!(plan.rewards, REWARDS_PER_SESSION * NUMBER_OF_DAYS as u64);
 
@@ -83,8 +86,7 @@ fn test_complete_all_sessions_succeeds() {
 
     let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
     assert_eq!(plan.attestations.len(), NUMBER_OF_DAYS as usize);
-    assert_eq!(plan.is_active, false);
-    assert_eq!(plan.is_completed, true);
+    assert_eq!(plan.status, PlanStatus::Completed);
     assert_eq!(plan.penalties, 0);
     // Rewards should be the total commitment stake - to handle rounded reward percentages
     assert_eq!(plan.rewards, COMMITMENT_STAKE);
@@ -123,7 +125,7 @@ fn test_complete_with_penalties_succeeds() {
         let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
         assert_eq!(
             plan.attestations.len(),
-            day as usize,
+            day,
             "There should be {} attestations after day {}",
             day,
             day
@@ -138,8 +140,7 @@ fn test_complete_with_penalties_succeeds() {
 
     let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
     assert_eq!(plan.attestations.len(), number_of_days);
-    assert_eq!(plan.is_active, true);
-    assert_eq!(plan.is_completed, false);
+    assert_eq!(plan.status, PlanStatus::Active);
     assert_eq!(plan.penalties, 0);
     assert_eq!(plan.rewards, REWARDS_PER_SESSION * number_of_days as u64);
 
@@ -149,7 +150,7 @@ fn test_complete_with_penalties_succeeds() {
     let balance = get_token_account_balance(&svm, &vault);
     assert_eq!(balance.unwrap(), FIFTY_USDC);
 
-    set_clock(&mut svm, plan.end_at + 1); // Set clock to after the plan end date
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1); // Set clock to after the dispute window
     let result = execute_complete(
         &mut svm,
         harness.usdc_mint,
@@ -173,13 +174,15 @@ fn test_complete_with_penalties_succeeds() {
     let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
     assert_eq!(balance.unwrap(), FIFTY_USDC + expected_rewards);
 
-    let balance = get_token_account_balance(&svm, &vault);
+    // The penalties go to the treasury, leaving the vault empty
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
     assert_eq!(balance.unwrap(), expected_penalties);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
 
     let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
-    assert_eq!(plan.attestations.len(), number_of_days as usize);
-    assert_eq!(plan.is_active, false);
-    assert_eq!(plan.is_completed, true);
+    assert_eq!(plan.attestations.len(), number_of_days);
+    assert_eq!(plan.status, PlanStatus::Completed);
     assert_eq!(plan.penalties, expected_penalties);
     assert_eq!(plan.rewards, expected_rewards);
 }
@@ -201,7 +204,7 @@ fn test_complete_without_any_sessions_succeeds() {
 
     let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
 
-    set_clock(&mut svm, plan.end_at + 1); // Set clock to after the plan end date
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1); // Set clock to after the dispute window
     let result = execute_complete(
         &mut svm,
         harness.usdc_mint,
@@ -215,13 +218,14 @@ fn test_complete_without_any_sessions_succeeds() {
     let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
     assert_eq!(balance.unwrap(), FIFTY_USDC);
 
-    let balance = get_token_account_balance(&svm, &vault);
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
     assert_eq!(balance.unwrap(), FIFTY_USDC);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
 
     let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
     assert_eq!(plan.attestations.len(), 0);
-    assert_eq!(plan.is_active, false);
-    assert_eq!(plan.is_completed, true);
+    assert_eq!(plan.status, PlanStatus::Completed);
     assert_eq!(plan.penalties, FIFTY_USDC);
     assert_eq!(plan.rewards, 0);
 }
@@ -259,7 +263,7 @@ fn test_inactive_plan_fails() {
 
     let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
     let new_plan = MeditationPlan {
-        is_active: false,
+        status: PlanStatus::Paused,
         ..plan.clone()
     };
     set_meditation_plan(&mut svm, meditation_plan, new_plan);
@@ -289,7 +293,7 @@ fn test_completed_plan_fails() {
 
     let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
     let new_plan = MeditationPlan {
-        is_completed: true,
+        status: PlanStatus::Completed,
         ..plan.clone()
     };
     set_meditation_plan(&mut svm, meditation_plan, new_plan);
@@ -342,3 +346,156 @@ fn test_plan_not_ended_fails() {
         "Incorrect error for plan not ended"
     );
 }
+
+#[test]
+fn test_complete_updates_practitioner() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    let practitioner = get_practitioner(&mut svm, harness.alice.pubkey());
+    assert_eq!(practitioner.lifetime_sessions, NUMBER_OF_DAYS as u64);
+    assert_eq!(practitioner.longest_streak, NUMBER_OF_DAYS as u32);
+    assert_eq!(practitioner.plans_completed, 1);
+    assert_eq!(practitioner.plans_failed, 0);
+    assert_eq!(
+        practitioner.reputation_score,
+        NUMBER_OF_DAYS as u64 * 10 + NUMBER_OF_DAYS as u64 * 5 + 100
+    );
+
+    // A failed plan without any sessions still creates the practitioner and counts against it
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1); // Set clock to after the dispute window
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    let practitioner = get_practitioner(&mut svm, harness.alice.pubkey());
+    assert_eq!(practitioner.plans_completed, 1);
+    assert_eq!(practitioner.plans_failed, 1);
+    assert_eq!(
+        practitioner.reputation_score,
+        NUMBER_OF_DAYS as u64 * 10 + NUMBER_OF_DAYS as u64 * 5 + 100 - 50
+    );
+}
+
+#[test]
+fn test_complete_returns_external_deposits() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    // Someone sends tokens straight to the vault on top of the stake
+    let external_deposit = 5 * USDC_TOKEN;
+    airdrop_usdc(
+        &mut svm,
+        harness.usdc_mint,
+        meditation_plan,
+        COMMITMENT_STAKE + external_deposit,
+    );
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1); // Set clock to after the dispute window
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.external_deposits, external_deposit);
+    assert_eq!(plan.penalties, COMMITMENT_STAKE);
+    assert_eq!(plan.rewards, 0);
+
+    // The external deposit goes back to the owner even though every session was missed
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC + external_deposit);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+}
+
+#[test]
+fn test_complete_vault_shortfall_settles() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    airdrop_usdc(
+        &mut svm,
+        harness.usdc_mint,
+        meditation_plan,
+        COMMITMENT_STAKE - 1,
+    );
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(
+        result.is_ok(),
+        "Complete should settle what the vault holds: {:?}",
+        result.err()
+    );
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.shortfall, 1);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE - 1);
+    assert_eq!(plan.penalties, 0);
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC + COMMITMENT_STAKE - 1);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+}
+
+#[test]
+fn test_complete_pays_beneficiary() {
+    let (mut svm, harness) = TestHarness::new();
+    let beneficiary = Keypair::new().pubkey();
+    let (meditation_plan, vault) = create_plan_with_beneficiary(&mut svm, &harness, beneficiary)
+        .expect("Initialize with a beneficiary should succeed");
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let beneficiary_ata = get_associated_token_address(&beneficiary, &harness.usdc_mint);
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        beneficiary_ata,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    // The owner signs and pays for the beneficiary's ATA, but the payout lands with the beneficiary
+    let balance = get_token_account_balance(&svm, &beneficiary_ata);
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+}
@@ -0,0 +1,192 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use capstone_client::instructions::{self, PlanParams};
+use capstone_client::pda;
+use litesvm::LiteSVM;
+use solana_signer::Signer;
+
+use crate::test_helpers::{
+    execute_measured, get_meditation_plan, set_clock, set_meditation_plan, TestHarness,
+    DURATION_MINUTES, STARTED_AT, USDC_TOKEN,
+};
+use crate::{MeditationAttestation, DAY_IN_SECONDS, HOUR_IN_SECONDS};
+
+/// Compute units measured for each benchmark, relative to `programs/capstone` - recorded with
+/// `UPDATE_COMPUTE_UNITS=1` and checked in
+const BASELINE_PATH: &str = "compute-units.txt";
+/// How far above the baseline a benchmark may go before it fails, in basis points
+const REGRESSION_THRESHOLD_BPS: u64 = 1_000;
+/// Default compute budget of a single-instruction transaction
+const DEFAULT_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+
+/// The largest plan, so `attest` can be measured with up to 119 stored attestations
+const NUMBER_OF_DAYS: u8 = 30;
+const DAILY_FREQUENCY: u8 = 4;
+/// Minimum stake, so the harness wallet can fund a plan per benchmark
+const COMMITMENT_STAKE: u64 = 10 * USDC_TOKEN;
+
+/// A session in the given slot of the plan, filling each day up to the daily frequency
+fn session(slot: u64) -> (i64, i64) {
+    let day = (slot / DAILY_FREQUENCY as u64) as i64;
+    let hour = (slot % DAILY_FREQUENCY as u64) as i64;
+    let started_at = STARTED_AT + day * DAY_IN_SECONDS + hour * HOUR_IN_SECONDS;
+    (started_at, started_at + DURATION_MINUTES as i64 * 60)
+}
+
+/// Initializes the largest plan at the minimum stake
+fn measure_initialize(svm: &mut LiteSVM, harness: &TestHarness, id: u64) -> u64 {
+    set_clock(svm, STARTED_AT);
+    let instruction = instructions::initialize(
+        harness.alice.pubkey(),
+        harness.alice_usdc_account,
+        harness.usdc_mint,
+        id,
+        PlanParams {
+            number_of_days: NUMBER_OF_DAYS,
+            daily_frequency: DAILY_FREQUENCY,
+            duration_minutes: DURATION_MINUTES,
+            commitment_stake: COMMITMENT_STAKE,
+        },
+        None,
+        None,
+        None,
+    );
+    execute_measured(svm, instruction, &[&harness.alice]).expect("Initialize should succeed")
+}
+
+/// Attests one more session on a plan already holding `prior` attestations
+fn measure_attest(svm: &mut LiteSVM, harness: &TestHarness, id: u64, prior: u64) -> u64 {
+    let meditation_plan = pda::meditation_plan(&harness.alice.pubkey(), id).0;
+    let (_account, mut plan) = get_meditation_plan(svm, &meditation_plan);
+    for slot in 0..prior {
+        let (started_at, ended_at) = session(slot);
+        plan.attestations.push(MeditationAttestation {
+            attester: harness.alice.pubkey(),
+            started_at,
+            ended_at,
+        });
+    }
+    plan.rewards = plan.reward_per_session * prior;
+    set_meditation_plan(svm, meditation_plan, plan);
+
+    let (started_at, ended_at) = session(prior);
+    set_clock(svm, ended_at + 1);
+    let instruction = instructions::attest(
+        harness.alice.pubkey(),
+        harness.alice.pubkey(),
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    execute_measured(svm, instruction, &[&harness.alice]).expect("Attest should succeed")
+}
+
+fn measure() -> BTreeMap<String, u64> {
+    let (mut svm, harness) = TestHarness::new();
+    let mut units = BTreeMap::new();
+
+    for (id, prior) in [(1, 0), (2, 60), (3, 119)] {
+        // The PDA bump search varies with the plan id, so keep the most expensive initialize
+        let initialize = measure_initialize(&mut svm, &harness, id);
+        let worst = units.entry("initialize".to_string()).or_insert(0);
+        *worst = initialize.max(*worst);
+        let attest = measure_attest(&mut svm, &harness, id, prior);
+        units.insert(format!("attest_after_{prior}"), attest);
+    }
+
+    // Plan 3 now has every session attested, so it can be completed straight away
+    let meditation_plan = pda::meditation_plan(&harness.alice.pubkey(), 3).0;
+    let instruction = instructions::complete(
+        harness.alice.pubkey(),
+        harness.usdc_mint,
+        meditation_plan,
+        harness.alice.pubkey(),
+        harness.alice_usdc_account,
+        None,
+    );
+    let complete = execute_measured(&mut svm, instruction, &[&harness.alice])
+        .expect("Complete should succeed");
+    units.insert("complete".to_string(), complete);
+
+    units
+}
+
+fn read_baseline() -> Option<BTreeMap<String, u64>> {
+    let baseline = fs::read_to_string(BASELINE_PATH).ok()?;
+    Some(
+        baseline
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+            .map(|line| {
+                let (name, units) = line
+                    .split_once(' ')
+                    .expect("Baseline lines should be `<benchmark> <units>`");
+                let units = units
+                    .trim()
+                    .parse()
+                    .expect("Baseline units should be a number");
+                (name.to_string(), units)
+            })
+            .collect(),
+    )
+}
+
+fn write_baseline(units: &BTreeMap<String, u64>) {
+    let mut baseline = String::from(
+        "# Compute units per benchmark in tests/compute_units.rs - rewrite with UPDATE_COMPUTE_UNITS=1\n",
+    );
+    for (name, units) in units {
+        baseline.push_str(&format!("{name} {units}\n"));
+    }
+    fs::write(BASELINE_PATH, baseline).expect("Baseline should be writable");
+}
+
+#[test]
+fn test_compute_units_within_baseline() {
+    let units = measure();
+
+    for (name, units) in &units {
+        assert!(
+            *units < DEFAULT_COMPUTE_UNIT_LIMIT,
+            "{name} uses {units} compute units, over the default limit"
+        );
+    }
+
+    if std::env::var("UPDATE_COMPUTE_UNITS").is_ok() {
+        write_baseline(&units);
+        println!("Recorded compute units in {BASELINE_PATH}: {units:?}");
+        return;
+    }
+    // Only measured numbers are worth failing on, so until one is recorded just report them
+    let Some(baseline) = read_baseline() else {
+        println!(
+            "{BASELINE_PATH} is missing - record it with UPDATE_COMPUTE_UNITS=1 and commit it: {units:?}"
+        );
+        return;
+    };
+
+    let mut regressions = vec![];
+    for (name, units) in &units {
+        let Some(&expected) = baseline.get(name) else {
+            regressions.push(format!(
+                "{name} has no baseline - run with UPDATE_COMPUTE_UNITS=1"
+            ));
+            continue;
+        };
+        let limit = expected + expected * REGRESSION_THRESHOLD_BPS / 10_000;
+        if *units > limit {
+            regressions.push(format!(
+                "{name} uses {units} compute units, baseline {expected}"
+            ));
+        } else if *units < expected {
+            println!("{name} improved to {units} compute units from {expected}");
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "Compute units regressed by more than {}%:\n{}",
+        REGRESSION_THRESHOLD_BPS / 100,
+        regressions.join("\n")
+    );
+}
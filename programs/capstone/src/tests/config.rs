@@ -0,0 +1,174 @@
+use capstone_client::instructions::{self, ConfigParams};
+use solana_instruction::Instruction;
+use solana_kite::send_transaction_from_instructions;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::test_helpers::{get_config, get_config_pda, TestHarness};
+
+fn build_update_config_instruction(
+    admin: Pubkey,
+    lending_program: Pubkey,
+    owner_yield_bps: u16,
+    penalty_fee_bps: u16,
+    yield_fee_bps: u16,
+    attestation_oracle: Pubkey,
+) -> Instruction {
+    instructions::update_config(
+        admin,
+        ConfigParams {
+            lending_program,
+            owner_yield_bps,
+            penalty_fee_bps,
+            yield_fee_bps,
+            attestation_oracle,
+        },
+    )
+}
+
+#[test]
+fn test_update_config_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let lending_program = Pubkey::new_unique();
+    let attestation_oracle = Pubkey::new_unique();
+
+    let instruction = build_update_config_instruction(
+        harness.admin.pubkey(),
+        lending_program,
+        2_500,
+        1_000,
+        500,
+        attestation_oracle,
+    );
+    let result = send_transaction_from_instructions(
+        &mut svm,
+        vec![instruction],
+        &[&harness.admin],
+        &harness.admin.pubkey(),
+    );
+    assert!(result.is_ok(), "Update config should succeed");
+
+    let config = get_config(&mut svm);
+    assert_eq!(config.admin, harness.admin.pubkey());
+    assert_eq!(config.lending_program, lending_program);
+    assert_eq!(config.owner_yield_bps, 2_500);
+    assert_eq!(config.penalty_fee_bps, 1_000);
+    assert_eq!(config.yield_fee_bps, 500);
+    assert_eq!(config.attestation_oracle, attestation_oracle);
+}
+
+#[test]
+fn test_update_config_invalid_basis_points_fails() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let instruction = build_update_config_instruction(
+        harness.admin.pubkey(),
+        Pubkey::default(),
+        10_001,
+        0,
+        0,
+        Pubkey::default(),
+    );
+    let result = send_transaction_from_instructions(
+        &mut svm,
+        vec![instruction],
+        &[&harness.admin],
+        &harness.admin.pubkey(),
+    );
+    assert!(result.is_err(), "Yield split above 100% should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidBasisPoints"),
+        "Incorrect error for invalid basis points"
+    );
+}
+
+#[test]
+fn test_update_config_invalid_fee_fails() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let instruction = build_update_config_instruction(
+        harness.admin.pubkey(),
+        Pubkey::default(),
+        10_000,
+        10_001,
+        0,
+        Pubkey::default(),
+    );
+    let result = send_transaction_from_instructions(
+        &mut svm,
+        vec![instruction],
+        &[&harness.admin],
+        &harness.admin.pubkey(),
+    );
+    assert!(result.is_err(), "Protocol fee above 100% should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidBasisPoints"),
+        "Incorrect error for invalid basis points"
+    );
+}
+
+#[test]
+fn test_unauthorized_update_config_fails() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let instruction = build_update_config_instruction(
+        harness.alice.pubkey(),
+        Pubkey::new_unique(),
+        5_000,
+        0,
+        0,
+        Pubkey::default(),
+    );
+    let result = send_transaction_from_instructions(
+        &mut svm,
+        vec![instruction],
+        &[&harness.alice],
+        &harness.alice.pubkey(),
+    );
+    assert!(result.is_err(), "Update config should fail for non-admin");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAdmin"),
+        "Incorrect error for unauthorized admin"
+    );
+}
+
+#[test]
+fn test_initialize_config_without_upgrade_authority_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    // Start from a clean slate - the harness writes the config directly
+    svm.set_account(get_config_pda().0, Default::default())
+        .unwrap();
+
+    let instruction = instructions::initialize_config(
+        harness.alice.pubkey(),
+        ConfigParams {
+            lending_program: Pubkey::default(),
+            owner_yield_bps: 10_000,
+            penalty_fee_bps: 0,
+            yield_fee_bps: 0,
+            attestation_oracle: Pubkey::default(),
+        },
+    );
+    let result = send_transaction_from_instructions(
+        &mut svm,
+        vec![instruction],
+        &[&harness.alice],
+        &harness.alice.pubkey(),
+    );
+    assert!(
+        result.is_err(),
+        "Only the upgrade authority should be able to create the config"
+    );
+    assert!(svm
+        .get_account(&get_config_pda().0)
+        .is_none_or(|account| account.data.is_empty()));
+}
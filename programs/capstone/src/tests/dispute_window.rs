@@ -0,0 +1,226 @@
+use solana_signer::Signer;
+
+use crate::test_helpers::{
+    attest_session, create_standard_plan, execute_attest, execute_complete,
+    execute_submit_late_attestation, get_meditation_plan, get_practitioner, set_clock, TestHarness,
+    COMMITMENT_STAKE, DURATION_MINUTES, NUMBER_OF_DAYS, REWARDS_PER_SESSION, STARTED_AT,
+};
+use crate::DAY_IN_SECONDS;
+
+/// A standard session on the given day of the plan
+fn session(day: i64) -> (i64, i64) {
+    let started_at = STARTED_AT + day * DAY_IN_SECONDS;
+    (started_at, started_at + DURATION_MINUTES as i64 * 60)
+}
+
+#[test]
+fn test_submit_late_attestation_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1);
+    assert!(plan.is_pending_settlement(plan.end_at + 1));
+
+    let (started_at, ended_at) = session(3);
+    let result = execute_submit_late_attestation(
+        &mut svm,
+        &harness.alice,
+        &harness.oracle,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    assert!(result.is_ok(), "Late attestation should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.attestations.len(), 1);
+    assert_eq!(plan.attestations[0].attester, harness.oracle.pubkey());
+    assert_eq!(plan.attestations[0].started_at, started_at);
+    assert_eq!(plan.rewards, REWARDS_PER_SESSION);
+
+    let practitioner = get_practitioner(&mut svm, harness.alice.pubkey());
+    assert_eq!(practitioner.lifetime_sessions, 1);
+}
+
+#[test]
+fn test_submit_late_attestation_without_oracle_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1);
+
+    let (started_at, ended_at) = session(3);
+    let result = execute_submit_late_attestation(
+        &mut svm,
+        &harness.alice,
+        &harness.bob,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    assert!(
+        result.is_err(),
+        "Late attestation without the oracle should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedOracle"),
+        "Incorrect error for unauthorized oracle"
+    );
+}
+
+#[test]
+fn test_submit_late_attestation_before_plan_ends_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let (started_at, ended_at) = session(0);
+    let result = execute_submit_late_attestation(
+        &mut svm,
+        &harness.alice,
+        &harness.oracle,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    assert!(
+        result.is_err(),
+        "Late attestation during the plan should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: DisputeWindowClosed"),
+        "Incorrect error for closed dispute window"
+    );
+}
+
+#[test]
+fn test_submit_late_attestation_after_dispute_window_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+
+    let (started_at, ended_at) = session(3);
+    let result = execute_submit_late_attestation(
+        &mut svm,
+        &harness.alice,
+        &harness.oracle,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    assert!(
+        result.is_err(),
+        "Late attestation after the window should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: DisputeWindowClosed"),
+        "Incorrect error for closed dispute window"
+    );
+}
+
+#[test]
+fn test_self_attestation_after_plan_ends_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1);
+
+    let (started_at, ended_at) = session(3);
+    let result = execute_attest(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    assert!(
+        result.is_err(),
+        "Self-attestation after the plan ends should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanExpired"),
+        "Incorrect error for expired plan"
+    );
+}
+
+#[test]
+fn test_complete_during_dispute_window_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at());
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(
+        result.is_err(),
+        "Complete during the dispute window should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: DisputeWindowActive"),
+        "Incorrect error for active dispute window"
+    );
+}
+
+#[test]
+fn test_complete_after_dispute_window_counts_late_attestations() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+    for day in 0..NUMBER_OF_DAYS as i64 - 1 {
+        attest_session(&mut svm, &harness.alice, meditation_plan, day);
+    }
+
+    // The last session failed to land before the plan ended
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1);
+    let (started_at, ended_at) = session(NUMBER_OF_DAYS as i64 - 1);
+    execute_submit_late_attestation(
+        &mut svm,
+        &harness.alice,
+        &harness.oracle,
+        meditation_plan,
+        started_at,
+        ended_at,
+    )
+    .expect("Late attestation should succeed");
+
+    // With every session attested the plan settles without waiting for the window to close
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.penalties, 0);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE);
+}
@@ -0,0 +1,116 @@
+use crate::test_helpers::{
+    attest_session, create_standard_plan, execute_extend_plan, get_meditation_plan, set_clock,
+    TestHarness, COMMITMENT_STAKE, NUMBER_OF_DAYS, REWARDS_PER_SESSION,
+};
+use crate::DAY_IN_SECONDS;
+
+#[test]
+fn test_extend_plan_rebalances_remaining_sessions() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_session(&mut svm, &harness.alice, meditation_plan, 0);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+
+    let additional_days = 7;
+    let result = execute_extend_plan(&mut svm, &harness.alice, meditation_plan, additional_days);
+    assert!(result.is_ok(), "Extending the plan should succeed");
+
+    let number_of_days = NUMBER_OF_DAYS + additional_days;
+    let expected_reward_per_session =
+        (COMMITMENT_STAKE - REWARDS_PER_SESSION) / (number_of_days as u64 - 1);
+
+    let (_account, extended_plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(extended_plan.number_of_days, number_of_days);
+    assert_eq!(
+        extended_plan.end_at,
+        plan.end_at + additional_days as i64 * DAY_IN_SECONDS
+    );
+    assert_eq!(extended_plan.rewards, REWARDS_PER_SESSION);
+    assert_eq!(
+        extended_plan.reward_per_session,
+        expected_reward_per_session
+    );
+
+    // Sessions past the original end date are now accepted
+    attest_session(&mut svm, &harness.alice, meditation_plan, 10);
+    let (_account, extended_plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(
+        extended_plan.rewards,
+        REWARDS_PER_SESSION + expected_reward_per_session
+    );
+}
+
+#[test]
+fn test_extend_plan_beyond_max_days_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_extend_plan(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        30 - NUMBER_OF_DAYS + 1,
+    );
+    assert!(result.is_err(), "Extending past 30 days should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidNumberOfDays"),
+        "Incorrect error for invalid number of days"
+    );
+}
+
+#[test]
+fn test_extend_plan_by_zero_days_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_extend_plan(&mut svm, &harness.alice, meditation_plan, 0);
+    assert!(result.is_err(), "Extending by zero days should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidNumberOfDays"),
+        "Incorrect error for invalid number of days"
+    );
+}
+
+#[test]
+fn test_extend_expired_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1); // Set clock to after the plan end date
+    let result = execute_extend_plan(&mut svm, &harness.alice, meditation_plan, 7);
+    assert!(result.is_err(), "Extending an expired plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanExpired"),
+        "Incorrect error for expired plan"
+    );
+}
+
+#[test]
+fn test_unauthorized_owner_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_extend_plan(&mut svm, &harness.bob, meditation_plan, 7);
+    assert!(
+        result.is_err(),
+        "Extending should fail when owner is not plan owner"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAccess"),
+        "Incorrect error for unauthorized"
+    );
+}
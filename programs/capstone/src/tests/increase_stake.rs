@@ -0,0 +1,199 @@
+use solana_kite::get_token_account_balance;
+
+use crate::test_helpers::{
+    attest_all_sessions, attest_session, create_standard_plan, execute_complete,
+    execute_increase_stake, get_meditation_plan, get_treasury_address, set_clock, TestHarness,
+    COMMITMENT_STAKE, FIFTY_USDC, NUMBER_OF_DAYS, REWARDS_PER_SESSION, USDC_TOKEN,
+};
+#[test]
+fn test_increase_stake_rebalances_future_sessions() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_session(&mut svm, &harness.alice, meditation_plan, 0);
+    attest_session(&mut svm, &harness.alice, meditation_plan, 1);
+
+    let top_up = 20 * USDC_TOKEN;
+    let result = execute_increase_stake(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        top_up,
+    );
+    assert!(result.is_ok(), "Increasing the stake should succeed");
+
+    // Past sessions keep their rate and the rest of the stake is spread over the remaining ones
+    let commitment_stake = COMMITMENT_STAKE + top_up;
+    let earned = REWARDS_PER_SESSION * 2;
+    let new_reward_per_session = (commitment_stake - earned) / (NUMBER_OF_DAYS as u64 - 2);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.commitment_stake, commitment_stake);
+    assert_eq!(plan.rewards, earned);
+    assert_eq!(plan.reward_per_session, new_reward_per_session);
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), commitment_stake);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC - top_up);
+
+    attest_session(&mut svm, &harness.alice, meditation_plan, 2);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.rewards, earned + new_reward_per_session);
+
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1); // Set clock to after the dispute window
+    execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Complete should succeed");
+
+    let expected_penalties = new_reward_per_session * (NUMBER_OF_DAYS as u64 - 3);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.penalties, expected_penalties);
+    assert_eq!(plan.rewards, commitment_stake - expected_penalties);
+
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), expected_penalties);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+}
+
+#[test]
+fn test_increase_stake_above_max_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_increase_stake(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        500 * USDC_TOKEN - COMMITMENT_STAKE + 1,
+    );
+    assert!(
+        result.is_err(),
+        "Increasing above the max stake should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidCommitmentStakeAmount"),
+        "Incorrect error for invalid stake"
+    );
+}
+
+#[test]
+fn test_increase_stake_zero_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_increase_stake(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        0,
+    );
+    assert!(result.is_err(), "Increasing by zero should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidCommitmentStakeAmount"),
+        "Incorrect error for invalid stake"
+    );
+}
+
+#[test]
+fn test_increase_stake_after_all_sessions_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let result = execute_increase_stake(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        USDC_TOKEN,
+    );
+    assert!(
+        result.is_err(),
+        "Increasing should fail once every session is attested"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: NoRemainingSessions"),
+        "Incorrect error for no remaining sessions"
+    );
+}
+
+#[test]
+fn test_increase_stake_expired_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1); // Set clock to after the plan end date
+    let result = execute_increase_stake(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        USDC_TOKEN,
+    );
+    assert!(result.is_err(), "Increasing an expired plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanExpired"),
+        "Incorrect error for expired plan"
+    );
+}
+
+#[test]
+fn test_unauthorized_owner_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_increase_stake(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.bob_usdc_account,
+        meditation_plan,
+        vault,
+        USDC_TOKEN,
+    );
+    assert!(
+        result.is_err(),
+        "Increasing should fail when owner is not plan owner"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAccess"),
+        "Incorrect error for unauthorized"
+    );
+}
@@ -1,12 +1,14 @@
+use solana_keypair::Keypair;
 use solana_kite::get_token_account_balance;
 use solana_signer::Signer;
 
 use crate::constants::DAY_IN_SECONDS;
 use crate::test_helpers::{
-    airdrop_usdc, create_fake_usdc_mint, execute_initialize, generate_id, get_meditation_plan,
-    TestHarness, COMMITMENT_STAKE, DAILY_FREQUENCY, DURATION_MINUTES, FIFTY_USDC, HUNDY_USDC,
-    NUMBER_OF_DAYS, USDC_TOKEN,
+    airdrop_usdc, create_fake_usdc_mint, create_plan_with_beneficiary, execute_initialize,
+    generate_id, get_meditation_plan, TestHarness, COMMITMENT_STAKE, DAILY_FREQUENCY,
+    DURATION_MINUTES, FIFTY_USDC, HUNDY_USDC, NUMBER_OF_DAYS, USDC_TOKEN,
 };
+use crate::PlanStatus;
 
 #[test]
 fn test_initialize_succeeds() {
@@ -41,13 +43,17 @@ fn test_initialize_succeeds() {
     assert_eq!(plan_account.owner, harness.program_id);
 
     assert_eq!(plan.attestations.len(), 0);
+    assert_eq!(
+        plan.beneficiary,
+        harness.alice.pubkey(),
+        "Beneficiary should default to the owner"
+    );
     assert_eq!(plan.bump, meditation_bump);
     assert_eq!(plan.commitment_stake, COMMITMENT_STAKE);
     assert_eq!(plan.daily_frequency, DAILY_FREQUENCY);
     assert_eq!(plan.duration_minutes, DURATION_MINUTES);
     assert_eq!(plan.id, id);
-    assert_eq!(plan.is_active, true);
-    assert_eq!(plan.is_completed, false);
+    assert_eq!(plan.status, PlanStatus::Active);
     assert_eq!(plan.number_of_days, NUMBER_OF_DAYS);
     assert_eq!(plan.owner, harness.alice.pubkey());
     assert_eq!(plan.penalties, 0);
@@ -56,6 +62,23 @@ fn test_initialize_succeeds() {
     assert_eq!(plan.end_at, NUMBER_OF_DAYS as i64 * DAY_IN_SECONDS);
 }
 
+#[test]
+fn test_initialize_with_beneficiary_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let beneficiary = Keypair::new().pubkey();
+
+    let result = create_plan_with_beneficiary(&mut svm, &harness, beneficiary);
+    assert!(
+        result.is_ok(),
+        "Initialize with a beneficiary should succeed"
+    );
+    let (meditation_plan, _vault) = result.unwrap();
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.owner, harness.alice.pubkey());
+    assert_eq!(plan.beneficiary, beneficiary);
+}
+
 #[test]
 fn test_duplicate_id_fails() {
     let (mut svm, harness) = TestHarness::new();
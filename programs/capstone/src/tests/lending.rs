@@ -0,0 +1,224 @@
+use solana_kite::get_token_account_balance;
+
+use crate::test_helpers::{
+    attest_all_sessions, execute_attest, execute_complete_with_lending,
+    execute_increase_stake_with_lending, execute_initialize_with_lending,
+    execute_renew_with_lending, expected_mock_lending_yield, generate_id, get_config,
+    get_meditation_plan, get_treasury_address, set_clock, set_config, setup_mock_lending,
+    TestHarness, COMMITMENT_STAKE, DURATION_MINUTES, FIFTY_USDC, HUNDY_USDC,
+    MOCK_LENDING_LIQUIDITY, REWARDS_PER_SESSION, STARTED_AT, USDC_TOKEN,
+};
+use crate::Config;
+
+#[test]
+fn test_initialize_with_lending_deposits_stake() {
+    let (mut svm, harness) = TestHarness::new();
+    let lending = setup_mock_lending(&mut svm, &harness, 10_000);
+
+    let result = execute_initialize_with_lending(&mut svm, &harness, &lending, generate_id());
+    assert!(result.is_ok(), "Initialize with lending should succeed");
+    let (meditation_plan, vault) = result.unwrap();
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC);
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0, "The stake should be lent out");
+
+    let balance = get_token_account_balance(&svm, &lending.reserve);
+    assert_eq!(balance.unwrap(), MOCK_LENDING_LIQUIDITY + COMMITMENT_STAKE);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.lending_program, lending.program_id);
+    assert_eq!(plan.accrued_yield, 0);
+}
+
+#[test]
+fn test_increase_stake_with_lending_deposits_top_up() {
+    let (mut svm, harness) = TestHarness::new();
+    let lending = setup_mock_lending(&mut svm, &harness, 10_000);
+
+    let (meditation_plan, vault) =
+        execute_initialize_with_lending(&mut svm, &harness, &lending, generate_id())
+            .expect("Initialize with lending should succeed");
+
+    let top_up = 20 * USDC_TOKEN;
+    let result = execute_increase_stake_with_lending(
+        &mut svm,
+        &harness,
+        &lending,
+        meditation_plan,
+        vault,
+        top_up,
+    );
+    assert!(result.is_ok(), "Increase stake with lending should succeed");
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0, "The top-up should be lent out");
+
+    let balance = get_token_account_balance(&svm, &lending.reserve);
+    assert_eq!(
+        balance.unwrap(),
+        MOCK_LENDING_LIQUIDITY + COMMITMENT_STAKE + top_up
+    );
+}
+
+#[test]
+fn test_complete_with_lending_pays_yield_split() {
+    let (mut svm, harness) = TestHarness::new();
+    let owner_yield_bps = 8_000;
+    let lending = setup_mock_lending(&mut svm, &harness, owner_yield_bps);
+
+    let (meditation_plan, vault) =
+        execute_initialize_with_lending(&mut svm, &harness, &lending, generate_id())
+            .expect("Initialize with lending should succeed");
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    let completed_at = plan.end_at + 1;
+    set_clock(&mut svm, completed_at);
+
+    let result =
+        execute_complete_with_lending(&mut svm, &harness, &lending, meditation_plan, vault);
+    assert!(result.is_ok(), "Complete with lending should succeed");
+
+    let accrued_yield = expected_mock_lending_yield(COMMITMENT_STAKE, completed_at - plan.start_at);
+    let owner_yield = accrued_yield * owner_yield_bps as u64 / 10_000;
+    assert!(accrued_yield > 0, "The stake should have earned yield");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.accrued_yield, accrued_yield);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE);
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC + owner_yield);
+
+    // The protocol's share of the yield goes to the treasury, leaving the vault empty
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), accrued_yield - owner_yield);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+
+    let balance = get_token_account_balance(&svm, &lending.reserve);
+    assert_eq!(balance.unwrap(), MOCK_LENDING_LIQUIDITY - accrued_yield);
+}
+
+#[test]
+fn test_complete_with_lending_and_penalties_pays_yield() {
+    let (mut svm, harness) = TestHarness::new();
+    let lending = setup_mock_lending(&mut svm, &harness, 10_000);
+
+    let (meditation_plan, vault) =
+        execute_initialize_with_lending(&mut svm, &harness, &lending, generate_id())
+            .expect("Initialize with lending should succeed");
+
+    let ended_at = STARTED_AT + (DURATION_MINUTES as i64 * 60);
+    set_clock(&mut svm, ended_at + 1);
+    execute_attest(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        STARTED_AT,
+        ended_at,
+    )
+    .expect("Attestation should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    let completed_at = plan.dispute_window_ends_at() + 1;
+    set_clock(&mut svm, completed_at);
+
+    let result =
+        execute_complete_with_lending(&mut svm, &harness, &lending, meditation_plan, vault);
+    assert!(result.is_ok(), "Complete with lending should succeed");
+
+    let accrued_yield = expected_mock_lending_yield(COMMITMENT_STAKE, completed_at - plan.start_at);
+    let expected_penalties = REWARDS_PER_SESSION * 6;
+    let expected_rewards = COMMITMENT_STAKE - expected_penalties;
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(
+        balance.unwrap(),
+        FIFTY_USDC + expected_rewards + accrued_yield,
+        "The owner keeps the yield even when sessions were missed"
+    );
+
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), expected_penalties);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+}
+
+#[test]
+fn test_renew_with_lending_keeps_lending() {
+    let (mut svm, harness) = TestHarness::new();
+    let lending = setup_mock_lending(&mut svm, &harness, 10_000);
+
+    let (meditation_plan, vault) =
+        execute_initialize_with_lending(&mut svm, &harness, &lending, generate_id())
+            .expect("Initialize with lending should succeed");
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    let renewed_at = plan.end_at + 1;
+    set_clock(&mut svm, renewed_at);
+
+    let result = execute_renew_with_lending(
+        &mut svm,
+        &harness,
+        &lending,
+        meditation_plan,
+        vault,
+        generate_id(),
+    );
+    assert!(result.is_ok(), "Renew with lending should succeed");
+    let (new_meditation_plan, new_vault) = result.unwrap();
+
+    let (_account, new_plan) = get_meditation_plan(&mut svm, &new_meditation_plan);
+    assert_eq!(new_plan.lending_program, lending.program_id);
+
+    let balance = get_token_account_balance(&svm, &new_vault);
+    assert_eq!(balance.unwrap(), 0, "The renewed stake should be lent out");
+
+    // The yield is paid out and the principal is lent again
+    let accrued_yield = expected_mock_lending_yield(COMMITMENT_STAKE, renewed_at - plan.start_at);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC + accrued_yield);
+
+    let balance = get_token_account_balance(&svm, &lending.reserve);
+    assert_eq!(
+        balance.unwrap(),
+        MOCK_LENDING_LIQUIDITY + COMMITMENT_STAKE - accrued_yield
+    );
+}
+
+#[test]
+fn test_initialize_with_lending_disabled_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let lending = setup_mock_lending(&mut svm, &harness, 10_000);
+
+    // Disable lending in the config after the pool exists
+    let config = get_config(&mut svm);
+    set_config(
+        &mut svm,
+        Config {
+            lending_program: Default::default(),
+            ..config
+        },
+    );
+
+    let result = execute_initialize_with_lending(&mut svm, &harness, &lending, generate_id());
+    assert!(
+        result.is_err(),
+        "Initialize should fail when lending is disabled"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidLendingProgram"),
+        "Incorrect error for disabled lending"
+    );
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC);
+}
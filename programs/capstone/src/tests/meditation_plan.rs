@@ -0,0 +1,533 @@
+use anchor_lang::error::Error;
+use anchor_lang::Result;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+use crate::error::MeditationPlanError;
+use crate::test_helpers::{
+    attest_fixture_session, standard_plan_fixture, COMMITMENT_STAKE, DURATION_MINUTES,
+    NUMBER_OF_DAYS, REWARDS_PER_SESSION, STARTED_AT,
+};
+use crate::{
+    MeditationAttestation, MeditationPlan, PlanStatus, DAY_IN_SECONDS, DISPUTE_WINDOW_SECONDS,
+    MAX_PAUSE_SECONDS, MAX_SCHEDULE_SECONDS,
+};
+
+const DURATION_SECONDS: i64 = DURATION_MINUTES as i64 * 60;
+
+fn assert_error<T: std::fmt::Debug>(result: Result<T>, expected: MeditationPlanError) {
+    match result.expect_err(&format!("Expected {expected:?}")) {
+        Error::AnchorError(error) => assert_eq!(
+            error.error_code_number,
+            u32::from(expected),
+            "Expected {expected:?}, got {}",
+            error.error_name
+        ),
+        error => panic!("Expected {expected:?}, got {error}"),
+    }
+}
+
+/// Attests a session starting at `started_at` with the clock at its end
+fn attest_at(plan: &mut MeditationPlan, started_at: i64, duration: i64) -> Result<()> {
+    let ended_at = started_at + duration;
+    plan.save_attestation(plan.owner, started_at, ended_at, ended_at)
+}
+
+fn attest_every_day(plan: &mut MeditationPlan) {
+    for day in 0..NUMBER_OF_DAYS as i64 {
+        attest_fixture_session(plan, day);
+    }
+}
+
+#[test]
+fn test_day_boundaries_bucket_sessions_from_plan_start() {
+    let mut plan = standard_plan_fixture();
+    attest_at(&mut plan, STARTED_AT, DURATION_SECONDS).expect("First second of day 0");
+
+    assert_error(
+        attest_at(&mut plan, STARTED_AT + DAY_IN_SECONDS - 1, DURATION_SECONDS),
+        MeditationPlanError::DailyFrequencyExceeded,
+    );
+    attest_at(&mut plan, STARTED_AT + DAY_IN_SECONDS, DURATION_SECONDS)
+        .expect("A new plan day starts exactly one day after the plan");
+}
+
+#[test]
+fn test_day_boundaries_with_offset_start() {
+    let mut plan = standard_plan_fixture();
+    let offset = 17 * 60 * 60;
+    plan.start_at += offset;
+    plan.end_at += offset;
+    let start_at = plan.start_at;
+
+    attest_at(&mut plan, start_at + 23 * 60 * 60, DURATION_SECONDS)
+        .expect("Plan days start at start_at, not midnight");
+    assert_error(
+        attest_at(&mut plan, start_at + DAY_IN_SECONDS - 60, DURATION_SECONDS),
+        MeditationPlanError::DailyFrequencyExceeded,
+    );
+    attest_at(&mut plan, start_at + DAY_IN_SECONDS, DURATION_SECONDS)
+        .expect("The next plan day starts a day after start_at");
+}
+
+#[test]
+fn test_daily_frequency_allows_several_sessions_a_day() {
+    let mut plan = standard_plan_fixture();
+    plan.daily_frequency = 3;
+
+    for hour in 0..3 {
+        attest_at(&mut plan, STARTED_AT + hour * 60 * 60, DURATION_SECONDS)
+            .expect("Sessions up to the daily frequency should succeed");
+    }
+    assert_error(
+        attest_at(&mut plan, STARTED_AT + 4 * 60 * 60, DURATION_SECONDS),
+        MeditationPlanError::DailyFrequencyExceeded,
+    );
+}
+
+#[test]
+fn test_session_must_fit_plan_dates() {
+    let mut plan = standard_plan_fixture();
+    plan.start_at = STARTED_AT + DAY_IN_SECONDS;
+
+    assert_error(
+        plan.save_attestation(
+            plan.owner,
+            STARTED_AT + 60,
+            STARTED_AT + 60 + DURATION_SECONDS,
+            plan.start_at + DAY_IN_SECONDS,
+        ),
+        MeditationPlanError::PlanNotStarted,
+    );
+
+    let mut plan = standard_plan_fixture();
+    let ended_at = plan.end_at + DURATION_SECONDS;
+    assert_error(
+        plan.save_attestation(plan.owner, plan.end_at, ended_at, ended_at),
+        MeditationPlanError::PlanExpired,
+    );
+    let started_at = plan.end_at - DURATION_SECONDS;
+    attest_at(&mut plan, started_at, DURATION_SECONDS)
+        .expect("A session ending right at end_at is within the plan");
+}
+
+#[test]
+fn test_session_timestamps_must_be_in_the_past() {
+    let mut plan = standard_plan_fixture();
+    let started_at = STARTED_AT + 60;
+    let ended_at = started_at + DURATION_SECONDS;
+
+    assert_error(
+        plan.save_attestation(plan.owner, started_at, ended_at, ended_at - 1),
+        MeditationPlanError::InvalidTimestamps,
+    );
+    assert_error(
+        plan.save_attestation(plan.owner, ended_at, started_at, ended_at),
+        MeditationPlanError::InvalidTimestamps,
+    );
+    assert_error(
+        plan.save_attestation(plan.owner, started_at, started_at, ended_at),
+        MeditationPlanError::InvalidTimestamps,
+    );
+}
+
+#[test]
+fn test_session_duration_bounds() {
+    let mut plan = standard_plan_fixture();
+
+    assert_error(
+        attest_at(&mut plan, STARTED_AT, DURATION_SECONDS - 1),
+        MeditationPlanError::AttestationTooShort,
+    );
+    attest_at(&mut plan, STARTED_AT, DURATION_SECONDS).expect("Exactly the plan duration");
+
+    assert_error(
+        attest_at(
+            &mut plan,
+            STARTED_AT + DAY_IN_SECONDS,
+            MeditationAttestation::MAX_DURATION + 1,
+        ),
+        MeditationPlanError::AttestationTooLong,
+    );
+    attest_at(
+        &mut plan,
+        STARTED_AT + DAY_IN_SECONDS,
+        MeditationAttestation::MAX_DURATION,
+    )
+    .expect("Exactly the maximum duration");
+    assert_eq!(plan.sessions_completed(), 2);
+}
+
+#[test]
+fn test_only_owner_can_attest() {
+    let mut plan = standard_plan_fixture();
+    let started_at = STARTED_AT + 60;
+    let ended_at = started_at + DURATION_SECONDS;
+
+    assert_error(
+        plan.save_attestation(Keypair::new().pubkey(), started_at, ended_at, ended_at),
+        MeditationPlanError::UnauthorizedAccess,
+    );
+    assert_eq!(plan.sessions_completed(), 0);
+}
+
+#[test]
+fn test_paused_plan_rejects_attestations() {
+    let mut plan = standard_plan_fixture();
+    plan.pause(STARTED_AT).expect("Pause should succeed");
+
+    assert_error(
+        attest_at(&mut plan, STARTED_AT + 60, DURATION_SECONDS),
+        MeditationPlanError::PlanInactive,
+    );
+}
+
+#[test]
+fn test_resume_extends_plan_by_paused_time_up_to_allowance() {
+    let mut plan = standard_plan_fixture();
+    let end_at = plan.end_at;
+
+    plan.pause(STARTED_AT).unwrap();
+    plan.resume(STARTED_AT + DAY_IN_SECONDS).unwrap();
+    assert_eq!(plan.end_at, end_at + DAY_IN_SECONDS);
+    assert_eq!(plan.status, PlanStatus::Active);
+
+    let now = STARTED_AT + 2 * DAY_IN_SECONDS;
+    plan.pause(now).unwrap();
+    plan.resume(now + 30 * DAY_IN_SECONDS).unwrap();
+    assert_eq!(plan.paused_seconds, MAX_PAUSE_SECONDS);
+    assert_eq!(plan.end_at, end_at + MAX_PAUSE_SECONDS);
+
+    assert_error(plan.resume(now), MeditationPlanError::PlanNotPaused);
+    assert_error(
+        plan.pause(now + DAY_IN_SECONDS),
+        MeditationPlanError::PauseLimitExceeded,
+    );
+}
+
+#[test]
+fn test_complete_all_sessions_returns_full_stake() {
+    let mut plan = standard_plan_fixture();
+    attest_every_day(&mut plan);
+
+    plan.complete(plan.end_at - 1)
+        .expect("Plans with every session attested can complete early");
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.completed_at, plan.end_at - 1);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE);
+    assert_eq!(plan.penalties, 0);
+}
+
+#[test]
+fn test_complete_without_sessions_forfeits_stake() {
+    let mut plan = standard_plan_fixture();
+
+    assert_error(
+        plan.complete(plan.end_at - 1),
+        MeditationPlanError::PlanNotEnded,
+    );
+    assert_error(
+        plan.complete(plan.dispute_window_ends_at()),
+        MeditationPlanError::DisputeWindowActive,
+    );
+
+    plan.complete(plan.dispute_window_ends_at() + 1).unwrap();
+    assert_eq!(plan.rewards, 0);
+    assert_eq!(plan.penalties, COMMITMENT_STAKE);
+}
+
+#[test]
+fn test_complete_penalizes_missed_sessions() {
+    let mut plan = standard_plan_fixture();
+    for day in 0..4 {
+        attest_fixture_session(&mut plan, day);
+    }
+
+    plan.complete(plan.end_at + DISPUTE_WINDOW_SECONDS + 1)
+        .unwrap();
+    let missed = NUMBER_OF_DAYS as u64 - 4;
+    assert_eq!(plan.penalties, REWARDS_PER_SESSION * missed);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE - plan.penalties);
+
+    assert_error(
+        plan.complete(plan.end_at + 2 * DAY_IN_SECONDS),
+        MeditationPlanError::PlanCompleted,
+    );
+}
+
+#[test]
+fn test_complete_applies_penalty_bps() {
+    let mut plan = standard_plan_fixture();
+    plan.penalty_bps = 2_500;
+    attest_fixture_session(&mut plan, 0);
+
+    plan.complete(plan.dispute_window_ends_at() + 1).unwrap();
+    let forfeited = REWARDS_PER_SESSION * (NUMBER_OF_DAYS as u64 - 1);
+    assert_eq!(plan.penalties, forfeited / 4);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE - plan.penalties);
+}
+
+#[test]
+fn test_settle_vault_returns_external_deposits() {
+    let mut plan = standard_plan_fixture();
+    attest_every_day(&mut plan);
+    plan.complete(plan.end_at).unwrap();
+    plan.accrued_yield = 1_000;
+
+    let (payout, protocol_yield) = plan
+        .settle_vault(COMMITMENT_STAKE + 1_000 + 5, 600)
+        .unwrap();
+    assert_eq!(payout, COMMITMENT_STAKE + 600 + 5);
+    assert_eq!(protocol_yield, 400);
+    assert_eq!(plan.external_deposits, 5);
+    assert_eq!(plan.shortfall, 0);
+}
+
+#[test]
+fn test_settle_vault_shortfall_comes_out_of_yield_and_penalties_first() {
+    let mut plan = standard_plan_fixture();
+    for day in 0..4 {
+        attest_fixture_session(&mut plan, day);
+    }
+    plan.complete(plan.dispute_window_ends_at() + 1).unwrap();
+    plan.accrued_yield = 1_000;
+    let penalties = plan.penalties;
+    let rewards = plan.rewards;
+
+    // Short by the protocol's yield and all but one unit of the penalties
+    let shortfall = 400 + penalties - 1;
+    let (payout, protocol_yield) = plan
+        .settle_vault(COMMITMENT_STAKE + 1_000 - shortfall, 600)
+        .unwrap();
+    assert_eq!(plan.shortfall, shortfall);
+    assert_eq!(protocol_yield, 0);
+    assert_eq!(plan.penalties, 1);
+    assert_eq!(plan.rewards, rewards);
+    assert_eq!(plan.accrued_yield, 600);
+    assert_eq!(payout, rewards + 600);
+}
+
+#[test]
+fn test_settle_vault_shortfall_reaches_rewards_last() {
+    let mut plan = standard_plan_fixture();
+    attest_every_day(&mut plan);
+    plan.complete(plan.end_at).unwrap();
+    plan.accrued_yield = 1_000;
+
+    // A lending market returned less than was lent
+    let vault_balance = COMMITMENT_STAKE - 2_000;
+    let (payout, protocol_yield) = plan.settle_vault(vault_balance, 600).unwrap();
+    assert_eq!(plan.shortfall, 3_000);
+    assert_eq!(protocol_yield, 0);
+    assert_eq!(plan.accrued_yield, 0);
+    assert_eq!(plan.penalties, 0);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE - 2_000);
+    assert_eq!(payout, vault_balance);
+}
+
+#[test]
+fn test_late_attestation_only_during_dispute_window() {
+    let mut plan = standard_plan_fixture();
+    let oracle = Keypair::new().pubkey();
+    let started_at = STARTED_AT + 60;
+    let ended_at = started_at + DURATION_SECONDS;
+
+    assert_error(
+        plan.save_late_attestation(oracle, started_at, ended_at, plan.end_at),
+        MeditationPlanError::DisputeWindowClosed,
+    );
+    plan.save_late_attestation(oracle, started_at, ended_at, plan.dispute_window_ends_at())
+        .expect("Late attestations are accepted until the window closes");
+    assert_eq!(plan.attestations[0].attester, oracle);
+    assert_error(
+        plan.save_late_attestation(
+            oracle,
+            started_at + DAY_IN_SECONDS,
+            ended_at + DAY_IN_SECONDS,
+            plan.dispute_window_ends_at() + 1,
+        ),
+        MeditationPlanError::DisputeWindowClosed,
+    );
+}
+
+#[test]
+fn test_increase_stake_keeps_credited_sessions() {
+    let mut plan = standard_plan_fixture();
+    attest_fixture_session(&mut plan, 0);
+
+    plan.increase_stake(COMMITMENT_STAKE, 6, STARTED_AT + DAY_IN_SECONDS)
+        .unwrap();
+    let remaining = NUMBER_OF_DAYS as u64 - 1;
+    assert_eq!(plan.rewards, REWARDS_PER_SESSION);
+    assert_eq!(
+        plan.reward_per_session,
+        (2 * COMMITMENT_STAKE - REWARDS_PER_SESSION) / remaining
+    );
+
+    assert_error(
+        plan.increase_stake(0, 6, STARTED_AT + DAY_IN_SECONDS),
+        MeditationPlanError::InvalidCommitmentStakeAmount,
+    );
+    assert_error(
+        plan.increase_stake(1, 6, plan.end_at + 1),
+        MeditationPlanError::PlanExpired,
+    );
+}
+
+#[test]
+fn test_extend_moves_end_and_respects_maximum() {
+    let mut plan = standard_plan_fixture();
+    let end_at = plan.end_at;
+
+    plan.extend(7, STARTED_AT).unwrap();
+    assert_eq!(plan.number_of_days, 14);
+    assert_eq!(plan.end_at, end_at + 7 * DAY_IN_SECONDS);
+    assert_eq!(plan.reward_per_session, COMMITMENT_STAKE / 14);
+
+    assert_error(
+        plan.extend(17, STARTED_AT),
+        MeditationPlanError::InvalidNumberOfDays,
+    );
+    assert_error(
+        plan.extend(0, STARTED_AT),
+        MeditationPlanError::InvalidNumberOfDays,
+    );
+}
+
+#[test]
+fn test_status_at_follows_the_clock() {
+    let mut plan = standard_plan_fixture();
+
+    assert_eq!(plan.status_at(plan.start_at), PlanStatus::Active);
+    assert_eq!(plan.status_at(plan.end_at), PlanStatus::Active);
+    assert_eq!(
+        plan.status_at(plan.end_at + 1),
+        PlanStatus::PendingSettlement
+    );
+    assert!(plan.is_pending_settlement(plan.dispute_window_ends_at()));
+    assert!(!plan.is_pending_settlement(plan.dispute_window_ends_at() + 1));
+
+    // Only a running plan moves with the clock
+    plan.pause(STARTED_AT).unwrap();
+    assert_eq!(plan.status_at(plan.end_at + 1), PlanStatus::Paused);
+
+    let mut plan = standard_plan_fixture();
+    plan.schedule(STARTED_AT - DAY_IN_SECONDS).unwrap();
+    assert_eq!(plan.status_at(plan.start_at - 1), PlanStatus::Scheduled);
+    assert_eq!(plan.status_at(plan.start_at), PlanStatus::Active);
+}
+
+#[test]
+fn test_schedule_start_bounds() {
+    let mut plan = standard_plan_fixture();
+    plan.schedule(STARTED_AT).unwrap();
+    assert_eq!(
+        plan.status,
+        PlanStatus::Active,
+        "Starting now isn't scheduled"
+    );
+
+    assert_error(
+        standard_plan_fixture().schedule(STARTED_AT + 1),
+        MeditationPlanError::InvalidStartTime,
+    );
+    assert_error(
+        standard_plan_fixture().schedule(STARTED_AT - MAX_SCHEDULE_SECONDS - 1),
+        MeditationPlanError::InvalidStartTime,
+    );
+    let mut plan = standard_plan_fixture();
+    plan.schedule(STARTED_AT - MAX_SCHEDULE_SECONDS).unwrap();
+    assert_eq!(plan.status, PlanStatus::Scheduled);
+}
+
+#[test]
+fn test_scheduled_plan_can_only_be_cancelled() {
+    let now = STARTED_AT - DAY_IN_SECONDS;
+    let mut plan = standard_plan_fixture();
+    plan.schedule(now).unwrap();
+
+    assert_error(
+        attest_at(&mut plan, now - DURATION_SECONDS, DURATION_SECONDS),
+        MeditationPlanError::PlanNotStarted,
+    );
+    assert_error(plan.pause(now), MeditationPlanError::PlanNotStarted);
+    assert_error(plan.complete(now), MeditationPlanError::PlanNotStarted);
+    assert_error(plan.retire(now), MeditationPlanError::PlanNotStarted);
+
+    plan.cancel(now).unwrap();
+    assert_eq!(plan.status, PlanStatus::Cancelled);
+    assert_eq!(
+        plan.status_at(plan.end_at + 1),
+        PlanStatus::Cancelled,
+        "A cancelled plan never starts"
+    );
+    assert_error(
+        plan.resume(plan.start_at),
+        MeditationPlanError::PlanInactive,
+    );
+    assert_error(plan.cancel(now), MeditationPlanError::PlanInactive);
+
+    plan.retire(plan.start_at).unwrap();
+    assert_eq!(plan.status, PlanStatus::Closed);
+}
+
+#[test]
+fn test_started_plan_cannot_be_cancelled() {
+    let mut plan = standard_plan_fixture();
+    assert_error(
+        plan.cancel(STARTED_AT),
+        MeditationPlanError::InvalidStatusTransition,
+    );
+    assert_error(
+        plan.cancel(plan.end_at + 1),
+        MeditationPlanError::InvalidStatusTransition,
+    );
+
+    plan.pause(STARTED_AT).unwrap();
+    assert_error(plan.cancel(STARTED_AT), MeditationPlanError::PlanInactive);
+}
+
+#[test]
+fn test_only_settled_or_cancelled_plan_can_be_closed() {
+    let mut plan = standard_plan_fixture();
+    assert_error(
+        plan.retire(STARTED_AT),
+        MeditationPlanError::InvalidStatusTransition,
+    );
+    assert_error(
+        plan.retire(plan.end_at + 1),
+        MeditationPlanError::InvalidStatusTransition,
+    );
+
+    plan.complete(plan.dispute_window_ends_at() + 1).unwrap();
+    plan.retire(plan.dispute_window_ends_at() + 1).unwrap();
+    assert_eq!(plan.status, PlanStatus::Closed);
+
+    let now = plan.dispute_window_ends_at() + 1;
+    assert_error(plan.retire(now), MeditationPlanError::PlanCompleted);
+    assert_error(plan.complete(now), MeditationPlanError::PlanCompleted);
+    assert_error(plan.cancel(now), MeditationPlanError::PlanCompleted);
+}
+
+#[test]
+fn test_pause_does_not_add_sessions() {
+    let mut plan = standard_plan_fixture();
+    plan.pause(STARTED_AT).unwrap();
+    plan.resume(STARTED_AT + DAY_IN_SECONDS + 60 * 60).unwrap();
+
+    for day in 1..=NUMBER_OF_DAYS as i64 {
+        attest_fixture_session(&mut plan, day);
+    }
+    assert!(plan.all_sessions_completed());
+    assert_eq!(plan.rewards, REWARDS_PER_SESSION * NUMBER_OF_DAYS as u64);
+
+    let started_at = STARTED_AT + (NUMBER_OF_DAYS as i64 + 1) * DAY_IN_SECONDS;
+    assert!(
+        started_at < plan.end_at,
+        "The pause leaves a partial plan day"
+    );
+    assert_error(
+        attest_at(&mut plan, started_at, DURATION_SECONDS),
+        MeditationPlanError::NoRemainingSessions,
+    );
+}
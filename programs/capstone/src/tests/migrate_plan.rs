@@ -0,0 +1,262 @@
+use anchor_lang::error::Error;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator, Space};
+use anchor_spl::token::spl_token::state::Account as SplTokenAccount;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::error::MeditationPlanError;
+use crate::test_helpers::{
+    attest_session, create_standard_plan, execute_attest, execute_complete, execute_migrate_plan,
+    get_meditation_plan, legacy_plan_data, set_legacy_meditation_plan, set_meditation_plan,
+    TestHarness, COMMITMENT_STAKE, DAILY_FREQUENCY, DURATION_MINUTES, ENDED_AT, LEGACY_PLAN_SPACE,
+    NUMBER_OF_DAYS, STARTED_AT,
+};
+use crate::{
+    MeditationAttestation, MeditationPlan, MeditationPlanV0, PlanStatus, Practitioner,
+    DAY_IN_SECONDS,
+};
+
+fn plan_fixture() -> MeditationPlan {
+    let owner = Keypair::new().pubkey();
+    let mut plan = MeditationPlan::new(
+        owner,
+        owner,
+        7,
+        254,
+        NUMBER_OF_DAYS,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        COMMITMENT_STAKE,
+        STARTED_AT,
+        Pubkey::default(),
+    );
+    plan.attestations.push(MeditationAttestation {
+        attester: owner,
+        started_at: STARTED_AT,
+        ended_at: ENDED_AT,
+    });
+    plan.rewards = plan.reward_per_session;
+    plan
+}
+
+fn read_legacy_plan(data: &[u8]) -> MeditationPlan {
+    let mut body = &data[MeditationPlan::DISCRIMINATOR.len()..];
+    let legacy =
+        MeditationPlanV0::deserialize(&mut body).expect("Legacy layout should deserialize");
+    MeditationPlan::from(legacy)
+}
+
+#[test]
+fn test_legacy_layout_matches_deployed_accounts() {
+    assert_eq!(MeditationPlanV0::SPACE, LEGACY_PLAN_SPACE);
+}
+
+#[test]
+fn test_legacy_plan_migrates() {
+    let plan = plan_fixture();
+    let data = legacy_plan_data(&plan);
+    assert_eq!(data.len(), MeditationPlanV0::SPACE);
+
+    let migrated = read_legacy_plan(&data);
+    assert_eq!(migrated.version, MeditationPlan::LAYOUT_VERSION);
+    assert_eq!(migrated.owner, plan.owner);
+    assert_eq!(migrated.beneficiary, plan.owner);
+    assert_eq!(migrated.id, plan.id);
+    assert_eq!(migrated.bump, plan.bump);
+    assert_eq!(migrated.number_of_days, NUMBER_OF_DAYS);
+    assert_eq!(migrated.daily_frequency, DAILY_FREQUENCY);
+    assert_eq!(migrated.duration_minutes, DURATION_MINUTES);
+    assert_eq!(migrated.commitment_stake, plan.commitment_stake);
+    assert_eq!(migrated.attestations.len(), 1);
+    assert_eq!(migrated.attestations[0].attester, plan.owner);
+    assert_eq!(migrated.attestations[0].started_at, STARTED_AT);
+    assert_eq!(migrated.attestations[0].ended_at, ENDED_AT);
+    assert_eq!(migrated.rewards, plan.rewards);
+    assert_eq!(migrated.penalties, 0);
+    assert_eq!(migrated.reward_per_session, plan.reward_per_session);
+    assert_eq!(migrated.penalty_bps, MeditationPlan::FULL_PENALTY_BPS);
+    assert_eq!(migrated.start_at, plan.start_at);
+    assert_eq!(migrated.end_at, plan.end_at);
+    assert_eq!(migrated.status, PlanStatus::Active);
+    assert_eq!(migrated.lending_program, Pubkey::default());
+    assert_eq!(migrated.charity, Pubkey::default());
+    assert_eq!(migrated.shortfall, 0);
+    assert_eq!(migrated.reserved, [0; 56]);
+}
+
+#[test]
+fn test_completed_legacy_plan_migrates_completed() {
+    let mut plan = plan_fixture();
+    plan.status = PlanStatus::Completed;
+    plan.penalties = COMMITMENT_STAKE - plan.rewards;
+
+    let migrated = read_legacy_plan(&legacy_plan_data(&plan));
+    assert_eq!(migrated.status, PlanStatus::Completed);
+    assert_eq!(migrated.penalties, plan.penalties);
+}
+
+/// A legacy plan owned by a fixture owner, holding the given number of attestations
+fn legacy_plan_fixture(sessions: i64) -> MeditationPlan {
+    let mut plan = plan_fixture();
+    plan.attestations.truncate(0);
+    for session in 0..sessions {
+        plan.attestations.push(MeditationAttestation {
+            attester: plan.owner,
+            started_at: STARTED_AT + session,
+            ended_at: ENDED_AT + session,
+        });
+    }
+    plan
+}
+
+#[test]
+fn test_discriminator_matches_account_attribute() {
+    let hash = anchor_lang::solana_program::hash::hash(b"account:MeditationPlan");
+    assert_eq!(MeditationPlan::DISCRIMINATOR, &hash.to_bytes()[..8]);
+}
+
+#[test]
+fn test_legacy_plan_is_not_migrated() {
+    // The legacy layout starts with the attestation count where the version now is, so a plan
+    // with one attestation would read as the current version if it weren't told apart by size
+    for sessions in [0, 1, 2, 7, 120] {
+        let data = legacy_plan_data(&legacy_plan_fixture(sessions));
+        match MeditationPlan::try_deserialize(&mut data.as_slice()) {
+            Err(Error::AnchorError(error)) => assert_eq!(
+                error.error_code_number,
+                u32::from(MeditationPlanError::PlanNotMigrated),
+                "A legacy plan with {sessions} sessions should not be migrated, got {}",
+                error.error_name
+            ),
+            Err(error) => panic!("Expected PlanNotMigrated, got {error}"),
+            Ok(_) => panic!("A legacy plan with {sessions} sessions should not deserialize"),
+        }
+    }
+}
+
+#[test]
+fn test_migrate_plan_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+    attest_session(&mut svm, &harness.alice, meditation_plan, 0);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_legacy_meditation_plan(&mut svm, meditation_plan, &plan);
+    let legacy_lamports = svm.get_account(&meditation_plan).unwrap().lamports;
+
+    let result = execute_migrate_plan(&mut svm, &harness.bob, meditation_plan);
+    assert!(result.is_ok(), "Migration should succeed");
+
+    let (account, migrated) = get_meditation_plan(&mut svm, &meditation_plan);
+    let space = MeditationPlan::DISCRIMINATOR.len() + MeditationPlan::INIT_SPACE;
+    assert_eq!(account.data.len(), space);
+    let rent_reserve = 3 * svm.minimum_balance_for_rent_exemption(SplTokenAccount::LEN)
+        + svm.minimum_balance_for_rent_exemption(
+            Practitioner::DISCRIMINATOR.len() + Practitioner::INIT_SPACE,
+        );
+    assert_eq!(
+        account.lamports - legacy_lamports,
+        svm.minimum_balance_for_rent_exemption(space)
+            - svm.minimum_balance_for_rent_exemption(LEGACY_PLAN_SPACE)
+            + rent_reserve,
+        "Payer should fund the rent for the larger account and the rent reserve"
+    );
+    assert_eq!(migrated.version, MeditationPlan::LAYOUT_VERSION);
+    assert_eq!(migrated.owner, plan.owner);
+    assert_eq!(migrated.commitment_stake, plan.commitment_stake);
+    assert_eq!(migrated.attestations.len(), 1);
+    assert_eq!(migrated.rewards, plan.rewards);
+    assert_eq!(migrated.status, PlanStatus::Active);
+
+    // The migrated plan is usable again
+    attest_session(&mut svm, &harness.alice, meditation_plan, 1);
+    let (_account, migrated) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(migrated.attestations.len(), 2);
+}
+
+#[test]
+fn test_unmigrated_plan_fails() {
+    for sessions in [1, 5] {
+        let (mut svm, harness) = TestHarness::new();
+        let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+        for day in 0..sessions {
+            attest_session(&mut svm, &harness.alice, meditation_plan, day);
+        }
+
+        let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+        set_legacy_meditation_plan(&mut svm, meditation_plan, &plan);
+
+        let (started_at, ended_at) = (
+            STARTED_AT + sessions * DAY_IN_SECONDS,
+            ENDED_AT + sessions * DAY_IN_SECONDS,
+        );
+        let result = execute_attest(
+            &mut svm,
+            &harness.alice,
+            meditation_plan,
+            started_at,
+            ended_at,
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Error Code: PlanNotMigrated"),
+            "Attesting a legacy plan with {sessions} sessions should fail as not migrated"
+        );
+
+        let result = execute_complete(
+            &mut svm,
+            harness.usdc_mint,
+            &harness.alice,
+            harness.alice_usdc_account,
+            meditation_plan,
+            vault,
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Error Code: PlanNotMigrated"),
+            "Completing a legacy plan with {sessions} sessions should fail as not migrated"
+        );
+    }
+}
+
+#[test]
+fn test_migrate_current_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_migrate_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Migrating a current plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanAlreadyMigrated"),
+        "Incorrect error for current plan"
+    );
+}
+
+#[test]
+fn test_migrate_unknown_layout_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, mut plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    plan.version = MeditationPlan::LAYOUT_VERSION + 1;
+    set_meditation_plan(&mut svm, meditation_plan, plan);
+
+    let result = execute_migrate_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Migrating an unknown layout should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidPlanLayout"),
+        "Incorrect error for unknown layout"
+    );
+}
@@ -0,0 +1,308 @@
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        metadata_pointer::MetadataPointer, non_transferable::NonTransferable,
+        BaseStateWithExtensions, StateWithExtensions,
+    },
+    state::{Account as Token2022Account, Mint as Token2022Mint},
+};
+use anchor_spl::token_interface::spl_token_metadata_interface::state::TokenMetadata;
+use litesvm::LiteSVM;
+use solana_kite::send_transaction_from_instructions;
+use solana_program_option::COption;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::test_helpers::{
+    attest_all_sessions, attest_session, create_standard_plan, execute_complete,
+    execute_mint_badge, execute_settle_expired, get_badge_mint, get_meditation_plan, set_clock,
+    TestHarness,
+};
+use crate::{BADGE_NAME, BADGE_SYMBOL};
+
+fn metadata_value(metadata: &TokenMetadata, key: &str) -> String {
+    metadata
+        .additional_metadata
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.clone())
+        .unwrap_or_else(|| panic!("Metadata field {} should exist", key))
+}
+
+/// Completes a plan with only the first `sessions` days attested, once missed sessions can be settled
+fn complete_plan(svm: &mut LiteSVM, harness: &TestHarness, sessions: i64) -> Pubkey {
+    let (meditation_plan, vault) = create_standard_plan(svm, harness);
+    for day in 0..sessions {
+        attest_session(svm, &harness.alice, meditation_plan, day);
+    }
+    let (_account, plan) = get_meditation_plan(svm, &meditation_plan);
+    set_clock(svm, plan.dispute_window_ends_at() + 1);
+    execute_complete(
+        svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Complete should succeed");
+    meditation_plan
+}
+
+#[test]
+fn test_mint_badge_success() {
+    let (mut svm, harness) = TestHarness::new();
+    let meditation_plan = complete_plan(&mut svm, &harness, 7);
+
+    let (badge_mint, owner_badge_ata) =
+        execute_mint_badge(&mut svm, &harness.alice, meditation_plan)
+            .expect("Minting the badge should succeed");
+    assert_eq!(badge_mint, get_badge_mint(meditation_plan));
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+
+    let mint_account = svm.get_account(&badge_mint).unwrap();
+    assert_eq!(mint_account.owner, spl_token_2022::ID);
+    let mint = StateWithExtensions::<Token2022Mint>::unpack(&mint_account.data).unwrap();
+    assert_eq!(mint.base.decimals, 0);
+    assert_eq!(mint.base.supply, 1);
+    assert_eq!(
+        mint.base.mint_authority,
+        COption::None,
+        "Mint authority should be removed after minting"
+    );
+    assert!(mint.get_extension::<NonTransferable>().is_ok());
+    let metadata_pointer = mint.get_extension::<MetadataPointer>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(metadata_pointer.metadata_address),
+        Some(badge_mint)
+    );
+
+    let metadata = mint.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.name, BADGE_NAME);
+    assert_eq!(metadata.symbol, BADGE_SYMBOL);
+    assert_eq!(
+        metadata_value(&metadata, "plan"),
+        meditation_plan.to_string()
+    );
+    assert_eq!(
+        metadata_value(&metadata, "sessions_completed"),
+        plan.total_sessions().to_string()
+    );
+    assert_eq!(metadata_value(&metadata, "completion_ratio_bps"), "10000");
+    assert_eq!(
+        metadata_value(&metadata, "completed_at"),
+        plan.completed_at.to_string()
+    );
+
+    let ata_account = svm.get_account(&owner_badge_ata).unwrap();
+    let ata = StateWithExtensions::<Token2022Account>::unpack(&ata_account.data).unwrap();
+    assert_eq!(ata.base.owner, harness.alice.pubkey());
+    assert_eq!(ata.base.amount, 1);
+}
+
+#[test]
+fn test_failed_plan_settles_without_badge() {
+    let (mut svm, harness) = TestHarness::new();
+    let meditation_plan = complete_plan(&mut svm, &harness, 2);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert!(plan.is_completed());
+    assert!(
+        svm.get_account(&get_badge_mint(meditation_plan)).is_none(),
+        "Settlement should not mint a badge"
+    );
+
+    let result = execute_mint_badge(&mut svm, &harness.alice, meditation_plan);
+    assert!(
+        result.is_err(),
+        "Minting should fail when sessions were missed"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: BadgeNotEarned"),
+        "Incorrect error for badge not earned"
+    );
+}
+
+#[test]
+fn test_mint_badge_with_prefunded_badge_address() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+    let badge_mint = get_badge_mint(meditation_plan);
+
+    // Anyone can send lamports to the predictable badge address
+    svm.airdrop(&badge_mint, 1).unwrap();
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+    execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Complete should succeed with a pre-funded badge address");
+
+    let (_badge_mint, owner_badge_ata) =
+        execute_mint_badge(&mut svm, &harness.alice, meditation_plan)
+            .expect("Minting should succeed with a pre-funded badge address");
+
+    let mint_account = svm.get_account(&badge_mint).unwrap();
+    assert_eq!(mint_account.owner, spl_token_2022::ID);
+    let ata_account = svm.get_account(&owner_badge_ata).unwrap();
+    let ata = StateWithExtensions::<Token2022Account>::unpack(&ata_account.data).unwrap();
+    assert_eq!(ata.base.amount, 1);
+}
+
+#[test]
+fn test_badge_transfer_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let meditation_plan = complete_plan(&mut svm, &harness, 7);
+    let (badge_mint, owner_badge_ata) =
+        execute_mint_badge(&mut svm, &harness.alice, meditation_plan)
+            .expect("Minting the badge should succeed");
+
+    let create_bob_ata = spl_associated_token_account::instruction::create_associated_token_account(
+        &harness.bob.pubkey(),
+        &harness.bob.pubkey(),
+        &badge_mint,
+        &spl_token_2022::ID,
+    );
+    let bob_badge_ata = spl_associated_token_account::get_associated_token_address_with_program_id(
+        &harness.bob.pubkey(),
+        &badge_mint,
+        &spl_token_2022::ID,
+    );
+    let transfer = spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::ID,
+        &owner_badge_ata,
+        &badge_mint,
+        &bob_badge_ata,
+        &harness.alice.pubkey(),
+        &[],
+        1,
+        0,
+    )
+    .unwrap();
+
+    let result = send_transaction_from_instructions(
+        &mut svm,
+        vec![create_bob_ata, transfer],
+        &[&harness.alice, &harness.bob],
+        &harness.alice.pubkey(),
+    );
+    assert!(result.is_err(), "Badge should not be transferable");
+}
+
+#[test]
+fn test_mint_badge_twice_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let meditation_plan = complete_plan(&mut svm, &harness, 7);
+    execute_mint_badge(&mut svm, &harness.alice, meditation_plan)
+        .expect("Minting the badge should succeed");
+    svm.expire_blockhash();
+
+    let result = execute_mint_badge(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Minting a second badge should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: BadgeAlreadyMinted"),
+        "Incorrect error for badge already minted"
+    );
+}
+
+#[test]
+fn test_mint_badge_after_settle_expired_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.settlement_deadline() + 1);
+    execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    )
+    .expect("Settling the expired plan should succeed");
+    assert!(
+        svm.get_account(&get_badge_mint(meditation_plan)).is_none(),
+        "Keepers should not mint the badge"
+    );
+
+    let (badge_mint, owner_badge_ata) =
+        execute_mint_badge(&mut svm, &harness.alice, meditation_plan)
+            .expect("Minting the badge should succeed");
+
+    let mint_account = svm.get_account(&badge_mint).unwrap();
+    let mint = StateWithExtensions::<Token2022Mint>::unpack(&mint_account.data).unwrap();
+    let metadata = mint.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata_value(&metadata, "completion_ratio_bps"), "10000");
+
+    let ata_account = svm.get_account(&owner_badge_ata).unwrap();
+    let ata = StateWithExtensions::<Token2022Account>::unpack(&ata_account.data).unwrap();
+    assert_eq!(ata.base.amount, 1);
+}
+
+#[test]
+fn test_mint_badge_before_complete_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+
+    let result = execute_mint_badge(&mut svm, &harness.alice, meditation_plan);
+    assert!(
+        result.is_err(),
+        "Minting should fail before the plan is completed"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanNotCompleted"),
+        "Incorrect error for plan not completed"
+    );
+}
+
+#[test]
+fn test_unauthorized_owner_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.settlement_deadline() + 1);
+    execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    )
+    .expect("Settling the expired plan should succeed");
+
+    let result = execute_mint_badge(&mut svm, &harness.bob, meditation_plan);
+    assert!(
+        result.is_err(),
+        "Minting should fail when owner is not plan owner"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAccess"),
+        "Incorrect error for unauthorized"
+    );
+}
@@ -6,3 +6,63 @@ mod complete;
 
 #[cfg(test)]
 mod initialize;
+
+#[cfg(test)]
+mod mint_badge;
+
+#[cfg(test)]
+mod config;
+
+#[cfg(test)]
+mod lending;
+
+#[cfg(test)]
+mod renew;
+
+#[cfg(test)]
+mod pause_plan;
+
+#[cfg(test)]
+mod cancel_plan;
+
+#[cfg(test)]
+mod close_plan;
+
+#[cfg(test)]
+mod increase_stake;
+
+#[cfg(test)]
+mod extend_plan;
+
+#[cfg(test)]
+mod settle_expired;
+
+#[cfg(test)]
+mod plan_template;
+
+#[cfg(test)]
+mod treasury;
+
+#[cfg(test)]
+mod charity;
+
+#[cfg(test)]
+mod dispute_window;
+
+#[cfg(test)]
+mod migrate_plan;
+
+#[cfg(test)]
+mod preview_settlement;
+
+#[cfg(test)]
+mod meditation_plan;
+
+#[cfg(test)]
+mod settlement_properties;
+
+#[cfg(test)]
+mod compute_units;
+
+#[cfg(test)]
+mod scenarios;
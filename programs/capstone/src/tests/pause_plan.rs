@@ -0,0 +1,302 @@
+use crate::test_helpers::{
+    create_standard_plan, execute_attest, execute_complete, execute_pause_plan,
+    execute_resume_plan, get_meditation_plan, set_clock, TestHarness, DURATION_MINUTES, ENDED_AT,
+    NUMBER_OF_DAYS, STARTED_AT,
+};
+use crate::{PlanStatus, DAY_IN_SECONDS, MAX_PAUSE_SECONDS};
+
+#[test]
+fn test_pause_and_resume_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+
+    let result = execute_pause_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_ok(), "Pausing should succeed");
+
+    let (_account, paused_plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(paused_plan.status, PlanStatus::Paused);
+    assert!(paused_plan.is_paused());
+    assert_eq!(paused_plan.paused_at, ENDED_AT + 1);
+
+    // Attestations are rejected while the plan is paused
+    let result = execute_attest(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        STARTED_AT,
+        ENDED_AT,
+    );
+    assert!(result.is_err(), "Attestation should fail while paused");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanInactive"),
+        "Incorrect error for paused plan"
+    );
+
+    let paused_seconds = 3 * DAY_IN_SECONDS;
+    set_clock(&mut svm, ENDED_AT + 1 + paused_seconds);
+    let result = execute_resume_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_ok(), "Resuming should succeed");
+
+    let (_account, resumed_plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(resumed_plan.status, PlanStatus::Active);
+    assert!(!resumed_plan.is_paused());
+    assert_eq!(resumed_plan.paused_seconds, paused_seconds);
+    assert_eq!(resumed_plan.end_at, plan.end_at + paused_seconds);
+
+    let started_at = ENDED_AT + 1 + paused_seconds;
+    let ended_at = started_at + (DURATION_MINUTES as i64 * 60);
+    set_clock(&mut svm, ended_at + 1);
+    let result = execute_attest(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    assert!(result.is_ok(), "Attestation should succeed after resuming");
+}
+
+#[test]
+fn test_resume_caps_extension_at_pause_limit() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+
+    execute_pause_plan(&mut svm, &harness.alice, meditation_plan).expect("Pausing should succeed");
+    set_clock(
+        &mut svm,
+        ENDED_AT + 1 + MAX_PAUSE_SECONDS + 6 * DAY_IN_SECONDS,
+    );
+    execute_resume_plan(&mut svm, &harness.alice, meditation_plan)
+        .expect("Resuming should succeed");
+
+    let (_account, resumed_plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(resumed_plan.paused_seconds, MAX_PAUSE_SECONDS);
+    assert_eq!(resumed_plan.end_at, plan.end_at + MAX_PAUSE_SECONDS);
+
+    let result = execute_pause_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(
+        result.is_err(),
+        "Pausing should fail once the allowance is used up"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PauseLimitExceeded"),
+        "Incorrect error for pause limit exceeded"
+    );
+}
+
+#[test]
+fn test_attest_after_resume_is_limited_to_planned_sessions() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    // A pause of a day and an hour leaves the plan with a partial extra day
+    execute_pause_plan(&mut svm, &harness.alice, meditation_plan).expect("Pausing should succeed");
+    set_clock(&mut svm, ENDED_AT + 1 + DAY_IN_SECONDS + 60 * 60);
+    execute_resume_plan(&mut svm, &harness.alice, meditation_plan)
+        .expect("Resuming should succeed");
+
+    for day in 1..=NUMBER_OF_DAYS as i64 {
+        let started_at = STARTED_AT + day * DAY_IN_SECONDS + 2 * 60 * 60;
+        let ended_at = started_at + (DURATION_MINUTES as i64 * 60);
+        set_clock(&mut svm, ended_at + 1);
+        execute_attest(
+            &mut svm,
+            &harness.alice,
+            meditation_plan,
+            started_at,
+            ended_at,
+        )
+        .expect("Attestation should succeed after resuming");
+    }
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert!(plan.all_sessions_completed());
+
+    let started_at = STARTED_AT + (NUMBER_OF_DAYS as i64 + 1) * DAY_IN_SECONDS;
+    let ended_at = started_at + (DURATION_MINUTES as i64 * 60);
+    assert!(
+        ended_at < plan.end_at,
+        "The pause leaves a partial plan day"
+    );
+    set_clock(&mut svm, ended_at + 1);
+    let result = execute_attest(
+        &mut svm,
+        &harness.alice,
+        meditation_plan,
+        started_at,
+        ended_at,
+    );
+    assert!(
+        result.is_err(),
+        "Attestation should fail once every planned session is attested"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: NoRemainingSessions"),
+        "Incorrect error for no remaining sessions"
+    );
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.attestations.len(), NUMBER_OF_DAYS as usize);
+}
+
+#[test]
+fn test_pause_paused_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    execute_pause_plan(&mut svm, &harness.alice, meditation_plan).expect("Pausing should succeed");
+
+    svm.expire_blockhash();
+    let result = execute_pause_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Pausing twice should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanInactive"),
+        "Incorrect error for paused plan"
+    );
+}
+
+#[test]
+fn test_resume_active_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_resume_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Resuming an active plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanNotPaused"),
+        "Incorrect error for plan not paused"
+    );
+}
+
+#[test]
+fn test_pause_expired_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1); // Set clock to after the plan end date
+    let result = execute_pause_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Pausing an expired plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanExpired"),
+        "Incorrect error for expired plan"
+    );
+}
+
+#[test]
+fn test_complete_paused_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    execute_pause_plan(&mut svm, &harness.alice, meditation_plan).expect("Pausing should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + 1); // Set clock to after the plan end date
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_err(), "Completing a paused plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanInactive"),
+        "Incorrect error for paused plan"
+    );
+}
+
+#[test]
+fn test_complete_paused_plan_after_settlement_deadline() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    execute_pause_plan(&mut svm, &harness.alice, meditation_plan).expect("Pausing should succeed");
+
+    // Once resuming could no longer extend the plan, a paused plan settles like an expired one
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.settlement_deadline() + 1);
+    execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Completing a paused plan past its settlement deadline should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.status, PlanStatus::Completed);
+}
+
+#[test]
+fn test_resume_completed_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+    execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Completion should succeed");
+
+    let result = execute_resume_plan(&mut svm, &harness.alice, meditation_plan);
+    assert!(result.is_err(), "Resuming a completed plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanCompleted"),
+        "Incorrect error for completed plan"
+    );
+}
+
+#[test]
+fn test_unauthorized_owner_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_pause_plan(&mut svm, &harness.bob, meditation_plan);
+    assert!(
+        result.is_err(),
+        "Pausing should fail when owner is not plan owner"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAccess"),
+        "Incorrect error for unauthorized"
+    );
+}
@@ -0,0 +1,257 @@
+use solana_kite::get_token_account_balance;
+use solana_signer::Signer;
+
+use crate::test_helpers::{
+    execute_complete, execute_create_plan_template, execute_initialize_from_template, generate_id,
+    get_meditation_plan, get_plan_template, get_treasury_address, set_clock, PlanTemplateParams,
+    TestHarness, COMMITMENT_STAKE, DAILY_FREQUENCY, DURATION_MINUTES, HUNDY_USDC, NUMBER_OF_DAYS,
+    USDC_TOKEN,
+};
+use crate::{MeditationPlan, PlanTemplate};
+
+#[test]
+fn test_create_plan_template_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let result = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        PlanTemplateParams::beginner(),
+    );
+    assert!(result.is_ok(), "Creating a template should succeed");
+
+    let template = get_plan_template(&mut svm, &result.unwrap());
+    assert_eq!(template.creator, harness.bob.pubkey());
+    assert_eq!(template.name, "Beginner 7-day");
+    assert_eq!(template.number_of_days, NUMBER_OF_DAYS);
+    assert_eq!(template.daily_frequency, DAILY_FREQUENCY);
+    assert_eq!(template.duration_minutes, DURATION_MINUTES);
+    assert_eq!(template.suggested_stake, COMMITMENT_STAKE);
+    assert_eq!(template.penalty_bps, 5_000);
+}
+
+#[test]
+fn test_create_plan_template_with_empty_name_fails() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let params = PlanTemplateParams {
+        name: String::new(),
+        ..PlanTemplateParams::beginner()
+    };
+    let result = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        params,
+    );
+    assert!(result.is_err(), "A template without a name should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidTemplateName"),
+        "Incorrect error for invalid template name"
+    );
+}
+
+#[test]
+fn test_create_plan_template_with_invalid_penalty_fails() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let params = PlanTemplateParams {
+        penalty_bps: MeditationPlan::FULL_PENALTY_BPS + 1,
+        ..PlanTemplateParams::beginner()
+    };
+    let result = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        params,
+    );
+    assert!(result.is_err(), "A penalty above 100% should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidBasisPoints"),
+        "Incorrect error for invalid basis points"
+    );
+}
+
+#[test]
+fn test_create_plan_template_with_low_penalty_fails() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let params = PlanTemplateParams {
+        penalty_bps: PlanTemplate::MIN_PENALTY_BPS - 1,
+        ..PlanTemplateParams::beginner()
+    };
+    let result = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        params,
+    );
+    assert!(result.is_err(), "A penalty below the minimum should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: TemplatePenaltyTooLow"),
+        "Incorrect error for template penalty too low"
+    );
+}
+
+#[test]
+fn test_create_plan_template_with_invalid_parameters_fails() {
+    let (mut svm, harness) = TestHarness::new();
+
+    let params = PlanTemplateParams {
+        number_of_days: 31,
+        ..PlanTemplateParams::beginner()
+    };
+    let result = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        params,
+    );
+    assert!(
+        result.is_err(),
+        "A template longer than 30 days should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidNumberOfDays"),
+        "Incorrect error for invalid number of days"
+    );
+}
+
+#[test]
+fn test_initialize_from_template_copies_parameters() {
+    let (mut svm, harness) = TestHarness::new();
+    let plan_template = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        PlanTemplateParams::beginner(),
+    )
+    .unwrap();
+
+    let result = execute_initialize_from_template(&mut svm, &harness, plan_template, None);
+    assert!(
+        result.is_ok(),
+        "Initializing from a template should succeed"
+    );
+    let (meditation_plan, vault) = result.unwrap();
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.owner, harness.alice.pubkey());
+    assert_eq!(plan.template, plan_template);
+    assert_eq!(plan.number_of_days, NUMBER_OF_DAYS);
+    assert_eq!(plan.daily_frequency, DAILY_FREQUENCY);
+    assert_eq!(plan.duration_minutes, DURATION_MINUTES);
+    assert_eq!(plan.commitment_stake, COMMITMENT_STAKE);
+    assert_eq!(plan.penalty_bps, 5_000);
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+}
+
+#[test]
+fn test_initialize_from_template_with_custom_stake() {
+    let (mut svm, harness) = TestHarness::new();
+    let plan_template = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        PlanTemplateParams::beginner(),
+    )
+    .unwrap();
+
+    let commitment_stake = 20 * USDC_TOKEN;
+    let (meditation_plan, vault) =
+        execute_initialize_from_template(&mut svm, &harness, plan_template, Some(commitment_stake))
+            .expect("Initializing with a custom stake should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.commitment_stake, commitment_stake);
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), commitment_stake);
+}
+
+#[test]
+fn test_initialize_from_template_with_invalid_stake_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let plan_template = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        PlanTemplateParams::beginner(),
+    )
+    .unwrap();
+
+    let result =
+        execute_initialize_from_template(&mut svm, &harness, plan_template, Some(USDC_TOKEN));
+    assert!(result.is_err(), "A stake below the minimum should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidCommitmentStakeAmount"),
+        "Incorrect error for invalid commitment stake"
+    );
+}
+
+#[test]
+fn test_template_penalty_applies_on_complete() {
+    let (mut svm, harness) = TestHarness::new();
+    let plan_template = execute_create_plan_template(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        generate_id(),
+        PlanTemplateParams::beginner(),
+    )
+    .unwrap();
+    let (meditation_plan, vault) =
+        execute_initialize_from_template(&mut svm, &harness, plan_template, None).unwrap();
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    // Half of the forfeited stake is returned under the template's 50% penalty
+    let expected_penalties = COMMITMENT_STAKE / 2;
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.penalties, expected_penalties);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE - expected_penalties);
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC - expected_penalties);
+
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), expected_penalties);
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+}
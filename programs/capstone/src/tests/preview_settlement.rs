@@ -0,0 +1,108 @@
+use crate::test_helpers::{
+    attest_fixture_session, attest_session, create_standard_plan, execute_complete,
+    execute_preview_settlement, get_meditation_plan, set_clock, standard_plan_fixture, TestHarness,
+    COMMITMENT_STAKE, NUMBER_OF_DAYS, REWARDS_PER_SESSION, STARTED_AT,
+};
+use crate::{PlanStatus, DAY_IN_SECONDS};
+
+#[test]
+fn test_projected_settlement_counts_missed_sessions() {
+    let mut plan = standard_plan_fixture();
+    attest_fixture_session(&mut plan, 0);
+    attest_fixture_session(&mut plan, 2);
+
+    let settlement = plan
+        .projected_settlement(STARTED_AT + 3 * DAY_IN_SECONDS + 60)
+        .unwrap();
+    assert_eq!(settlement.missed_sessions, 1, "Only day 1 was missed");
+    assert_eq!(settlement.remaining_sessions, NUMBER_OF_DAYS as u64 - 2);
+    assert_eq!(
+        settlement.penalties,
+        REWARDS_PER_SESSION * (NUMBER_OF_DAYS as u64 - 2)
+    );
+    assert_eq!(settlement.rewards, COMMITMENT_STAKE - settlement.penalties);
+
+    let settlement = plan.projected_settlement(plan.end_at + 1).unwrap();
+    assert_eq!(
+        settlement.missed_sessions, settlement.remaining_sessions,
+        "Every remaining session is missed once the plan has ended"
+    );
+}
+
+#[test]
+fn test_projected_settlement_skips_paused_days() {
+    let mut plan = standard_plan_fixture();
+    attest_fixture_session(&mut plan, 0);
+    plan.status = PlanStatus::Paused;
+    plan.paused_at = STARTED_AT + DAY_IN_SECONDS;
+
+    let settlement = plan
+        .projected_settlement(STARTED_AT + 4 * DAY_IN_SECONDS)
+        .unwrap();
+    assert_eq!(
+        settlement.missed_sessions, 0,
+        "Days spent paused aren't missed"
+    );
+}
+
+#[test]
+fn test_projected_settlement_of_completed_plan_is_final() {
+    let mut plan = standard_plan_fixture();
+    attest_fixture_session(&mut plan, 0);
+    plan.status = PlanStatus::Completed;
+    plan.rewards = 1_000;
+    plan.penalties = 2_000;
+
+    let settlement = plan.projected_settlement(plan.end_at + 1).unwrap();
+    assert_eq!(settlement.rewards, 1_000);
+    assert_eq!(settlement.penalties, 2_000);
+}
+
+#[test]
+fn test_preview_settlement_matches_complete() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+    for day in 0..3 {
+        attest_session(&mut svm, &harness.alice, meditation_plan, day);
+    }
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+    let settlement = execute_preview_settlement(&mut svm, &harness.bob, meditation_plan)
+        .expect("Anyone should be able to preview the settlement");
+    assert_eq!(settlement.missed_sessions, NUMBER_OF_DAYS as u64 - 3);
+    assert_eq!(settlement.remaining_sessions, NUMBER_OF_DAYS as u64 - 3);
+
+    execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Complete should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(settlement.rewards, plan.rewards);
+    assert_eq!(settlement.penalties, plan.penalties);
+}
+
+#[test]
+fn test_preview_settlement_leaves_plan_unchanged() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, _vault) = create_standard_plan(&mut svm, &harness);
+    attest_session(&mut svm, &harness.alice, meditation_plan, 0);
+
+    let (before, _plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    let settlement = execute_preview_settlement(&mut svm, &harness.alice, meditation_plan)
+        .expect("Preview should succeed");
+    let (after, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+
+    assert_eq!(
+        before.data, after.data,
+        "Preview should not write to the plan"
+    );
+    assert_eq!(plan.status, PlanStatus::Active);
+    assert_eq!(settlement.rewards, COMMITMENT_STAKE - settlement.penalties);
+}
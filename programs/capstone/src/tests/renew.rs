@@ -0,0 +1,236 @@
+use solana_kite::get_token_account_balance;
+
+use crate::test_helpers::{
+    attest_all_sessions, create_standard_plan, execute_renew, generate_id, get_meditation_plan,
+    get_practitioner, get_treasury_address, set_clock, TestHarness, COMMITMENT_STAKE,
+    DAILY_FREQUENCY, DURATION_MINUTES, FIFTY_USDC, NUMBER_OF_DAYS, USDC_TOKEN,
+};
+use crate::PlanStatus;
+
+#[test]
+fn test_renew_rolls_payout_into_new_plan() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let new_id = generate_id();
+    let result = execute_renew(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        new_id,
+        30,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        COMMITMENT_STAKE,
+    );
+    assert!(result.is_ok(), "Renew should succeed");
+    let (new_meditation_plan, new_vault) = result.unwrap();
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE);
+
+    let (_account, new_plan) = get_meditation_plan(&mut svm, &new_meditation_plan);
+    assert_eq!(new_plan.id, new_id);
+    assert_eq!(new_plan.owner, plan.owner);
+    assert_eq!(new_plan.beneficiary, plan.beneficiary);
+    assert_eq!(new_plan.status, PlanStatus::Active);
+    assert!(!new_plan.is_lending());
+    assert_eq!(new_plan.number_of_days, 30);
+    assert_eq!(new_plan.commitment_stake, COMMITMENT_STAKE);
+    assert_eq!(new_plan.start_at, plan.completed_at);
+    assert!(new_plan.attestations.is_empty());
+
+    // The payout moved vault to vault without touching the owner's balance
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+    let balance = get_token_account_balance(&svm, &new_vault);
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC);
+
+    let practitioner = get_practitioner(&mut svm, plan.owner);
+    assert_eq!(practitioner.plans_completed, 1);
+}
+
+#[test]
+fn test_renew_with_smaller_stake_returns_surplus() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let new_stake = 20 * USDC_TOKEN;
+    let (_new_meditation_plan, new_vault) = execute_renew(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        generate_id(),
+        NUMBER_OF_DAYS,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        new_stake,
+    )
+    .expect("Renew should succeed");
+
+    let balance = get_token_account_balance(&svm, &new_vault);
+    assert_eq!(balance.unwrap(), new_stake);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC + COMMITMENT_STAKE - new_stake);
+}
+
+#[test]
+fn test_renew_with_larger_stake_tops_up_from_owner() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let new_stake = 80 * USDC_TOKEN;
+    let (_new_meditation_plan, new_vault) = execute_renew(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        generate_id(),
+        NUMBER_OF_DAYS,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        new_stake,
+    )
+    .expect("Renew should succeed");
+
+    let balance = get_token_account_balance(&svm, &new_vault);
+    assert_eq!(balance.unwrap(), new_stake);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC + COMMITMENT_STAKE - new_stake);
+}
+
+#[test]
+fn test_renew_after_missed_sessions_tops_up_from_owner() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1); // Set clock to after the dispute window
+    let (_new_meditation_plan, new_vault) = execute_renew(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        generate_id(),
+        NUMBER_OF_DAYS,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        COMMITMENT_STAKE,
+    )
+    .expect("Renew should succeed");
+
+    // The penalty goes to the treasury and the new stake comes entirely from the owner
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+    let balance = get_token_account_balance(&svm, &new_vault);
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC - COMMITMENT_STAKE);
+}
+
+#[test]
+fn test_renew_plan_not_ended_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let result = execute_renew(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        generate_id(),
+        NUMBER_OF_DAYS,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        COMMITMENT_STAKE,
+    );
+    assert!(result.is_err(), "Renew should fail before the plan ends");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanNotEnded"),
+        "Incorrect error for plan not ended"
+    );
+}
+
+#[test]
+fn test_renew_invalid_parameters_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let result = execute_renew(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+        generate_id(),
+        31,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        COMMITMENT_STAKE,
+    );
+    assert!(result.is_err(), "Renew should fail with invalid parameters");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidNumberOfDays"),
+        "Incorrect error for invalid number of days"
+    );
+}
+
+#[test]
+fn test_unauthorized_owner_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let result = execute_renew(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.bob_usdc_account,
+        meditation_plan,
+        vault,
+        generate_id(),
+        NUMBER_OF_DAYS,
+        DAILY_FREQUENCY,
+        DURATION_MINUTES,
+        COMMITMENT_STAKE,
+    );
+    assert!(
+        result.is_err(),
+        "Renew should fail when owner is not plan owner"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAccess"),
+        "Incorrect error for unauthorized"
+    );
+}
@@ -0,0 +1,172 @@
+use solana_signer::Signer;
+
+use crate::scenario::Scenario;
+use crate::test_helpers::{COMMITMENT_STAKE, REWARDS_PER_SESSION, USDC_TOKEN};
+use crate::{PlanStatus, DAY_IN_SECONDS};
+
+#[test]
+fn test_scenario_perfect_week() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x1x20m 50 USDC
+         days 0-6 attest 1
+         day 6 complete",
+    )
+    .run();
+
+    let plan = outcome.plan();
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.penalties, 0);
+    assert_eq!(plan.rewards, COMMITMENT_STAKE);
+    assert_eq!(outcome.usdc_change(outcome.harness.alice.pubkey()), 0);
+}
+
+#[test]
+fn test_scenario_partial_plan() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x2x10m 50 USDC; day 1 attest 2; day 3 attest 1; day 8 complete",
+    )
+    .run();
+
+    let plan = outcome.plan();
+    let reward_per_session = COMMITMENT_STAKE / 14;
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.attestations.len(), 3);
+    assert_eq!(plan.penalties, 11 * reward_per_session);
+    assert_eq!(plan.rewards + plan.penalties, COMMITMENT_STAKE);
+    assert_eq!(
+        outcome.usdc_change(outcome.harness.alice.pubkey()),
+        plan.rewards as i64 - COMMITMENT_STAKE as i64
+    );
+}
+
+#[test]
+fn test_scenario_pause_for_a_retreat() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x1x20m 50 USDC
+         days 0-1 attest 1
+         day 2 pause                          // three days away
+         day 3 attest 1 fails PlanInactive
+         day 5 resume
+         days 5-9 attest 1                    // the plan ends three days later
+         day 10 complete",
+    )
+    .run();
+
+    let plan = outcome.plan();
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.paused_seconds, 3 * DAY_IN_SECONDS);
+    assert_eq!(plan.end_at, plan.start_at + 10 * DAY_IN_SECONDS);
+    assert_eq!(plan.penalties, 0);
+    assert_eq!(outcome.usdc_change(outcome.harness.alice.pubkey()), 0);
+}
+
+#[test]
+fn test_scenario_extend_a_good_streak() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x1x20m 50 USDC
+         days 0-2 attest 1
+         day 3 extend 3
+         days 3-9 attest 1
+         day 10 complete",
+    )
+    .run();
+
+    let plan = outcome.plan();
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.number_of_days, 10);
+    assert_eq!(plan.attestations.len(), 10);
+    assert_eq!(plan.penalties, 0);
+    assert_eq!(outcome.usdc_change(outcome.harness.alice.pubkey()), 0);
+}
+
+#[test]
+fn test_scenario_increase_stake_midway() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x1x20m 50 USDC
+         days 0-2 attest 1
+         day 3 stake 20 USDC
+         day 3 attest 1
+         day 8 complete",
+    )
+    .run();
+
+    // The unearned 70 USDC less three sessions' rewards is spread over the four sessions left
+    let rebalanced_reward = (70 * USDC_TOKEN - 3 * REWARDS_PER_SESSION) / 4;
+    let plan = outcome.plan();
+    assert_eq!(plan.commitment_stake, 70 * USDC_TOKEN);
+    assert_eq!(plan.penalties, 3 * rebalanced_reward);
+    assert_eq!(plan.rewards + plan.penalties, 70 * USDC_TOKEN);
+    assert_eq!(
+        outcome.usdc_change(outcome.harness.alice.pubkey()),
+        plan.rewards as i64 - 70 * USDC_TOKEN as i64
+    );
+}
+
+#[test]
+fn test_scenario_late_attestation_in_dispute_window() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x1x20m 50 USDC
+         days 0-5 attest 1
+         day 7 attest 1 fails PlanExpired     // the last session was never submitted
+         day 7 late attest day 6
+         day 7 complete",
+    )
+    .run();
+
+    let plan = outcome.plan();
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.attestations.len(), 7);
+    assert_eq!(plan.penalties, 0);
+    assert_eq!(outcome.usdc_change(outcome.harness.alice.pubkey()), 0);
+}
+
+#[test]
+fn test_scenario_complete_waits_for_dispute_window() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x1x20m 50 USDC
+         days 0-4 attest 1
+         day 7 complete fails DisputeWindowActive
+         day 8 complete",
+    )
+    .run();
+
+    let plan = outcome.plan();
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.penalties, 2 * REWARDS_PER_SESSION);
+    assert_eq!(
+        outcome.usdc_change(outcome.harness.alice.pubkey()),
+        plan.rewards as i64 - COMMITMENT_STAKE as i64
+    );
+}
+
+#[test]
+fn test_scenario_keeper_settles_forgotten_plan() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x1x20m 50 USDC
+         days 0-6 attest 1
+         day 9 settle fails GracePeriodActive
+         day 10 settle",
+    )
+    .run();
+
+    let plan = outcome.plan();
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(outcome.usdc_change(outcome.harness.alice.pubkey()), 0);
+    assert_eq!(outcome.usdc_change(outcome.harness.bob.pubkey()), 0);
+}
+
+#[test]
+fn test_scenario_daily_frequency_limit() {
+    let mut outcome = Scenario::parse(
+        "day 0 create 7x2x10m 50 USDC
+         day 0 attest 3 fails DailyFrequencyExceeded
+         day 1 attest 2",
+    )
+    .run();
+
+    let plan = outcome.plan();
+    assert_eq!(plan.status, PlanStatus::Active);
+    assert_eq!(plan.attestations.len(), 4);
+    assert_eq!(plan.rewards, 4 * (COMMITMENT_STAKE / 14));
+    assert_eq!(outcome.vault_balance(), COMMITMENT_STAKE);
+}
@@ -0,0 +1,330 @@
+use capstone_client::{instructions, pda};
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_kite::{get_token_account_balance, send_transaction_from_instructions};
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::test_helpers::{
+    attest_all_sessions, attest_session, create_plan_with_beneficiary, create_plan_with_charity,
+    create_standard_plan, execute_complete, execute_pause_plan, execute_register_charity,
+    execute_settle_expired, get_meditation_plan, get_practitioner, get_treasury_address, set_clock,
+    TestHarness, COMMITMENT_STAKE, FIFTY_USDC, REWARDS_PER_SESSION,
+};
+use crate::{CharityKind, PlanStatus, MAX_PAUSE_SECONDS, SETTLEMENT_GRACE_SECONDS};
+
+#[test]
+fn test_settle_expired_pays_owner() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_session(&mut svm, &harness.alice, meditation_plan, 0);
+    let (plan_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    let rent_exempt = svm.minimum_balance_for_rent_exemption(plan_account.data.len());
+    let rent_reserve = plan_account.lamports - rent_exempt;
+    assert!(rent_reserve > 0, "The plan should hold a rent reserve");
+
+    let owner_lamports = svm.get_balance(&harness.alice.pubkey()).unwrap();
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS + 1);
+    let result = execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Settling an expired plan should succeed");
+
+    let (plan_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.status, PlanStatus::Completed);
+    assert_eq!(plan.rewards, REWARDS_PER_SESSION);
+    assert_eq!(plan.penalties, COMMITMENT_STAKE - REWARDS_PER_SESSION);
+    assert_eq!(plan_account.lamports, rent_exempt);
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), FIFTY_USDC + REWARDS_PER_SESSION);
+
+    // The owner's ATA already existed, so the rent reserve goes back to the owner
+    let balance = svm.get_balance(&harness.alice.pubkey()).unwrap();
+    assert_eq!(balance, owner_lamports + rent_reserve);
+
+    let practitioner = get_practitioner(&mut svm, harness.alice.pubkey());
+    assert_eq!(practitioner.plans_failed, 1);
+}
+
+#[test]
+fn test_settle_expired_reimburses_keeper_for_owner_ata() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    // The owner closed their USDC account after initializing the plan
+    svm.set_account(harness.alice_usdc_account, Account::default())
+        .unwrap();
+
+    let (plan_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    let rent_exempt = svm.minimum_balance_for_rent_exemption(plan_account.data.len());
+    let rent_reserve = plan_account.lamports - rent_exempt;
+
+    let keeper_lamports = svm.get_balance(&harness.bob.pubkey()).unwrap();
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS + 1);
+    execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    )
+    .expect("Settling an expired plan should succeed");
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+
+    let keeper_cost = keeper_lamports - svm.get_balance(&harness.bob.pubkey()).unwrap();
+    assert!(
+        keeper_cost < rent_reserve,
+        "Keeper should be reimbursed for the ATA rent"
+    );
+}
+
+#[test]
+fn test_settle_expired_keeper_breaks_even() {
+    let (mut svm, harness) = TestHarness::new();
+    let wallet = Pubkey::new_unique();
+    let charity = execute_register_charity(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        wallet,
+        "Mindful Schools",
+        CharityKind::Charity,
+    )
+    .unwrap();
+    let (meditation_plan, _vault) = create_plan_with_charity(&mut svm, &harness, charity).unwrap();
+
+    // With no session attested the owner has no practitioner account, and every ATA the
+    // settlement pays into is gone
+    let practitioner = pda::practitioner(&harness.alice.pubkey()).0;
+    assert!(svm.get_account(&practitioner).is_none());
+    let charity_ata = get_associated_token_address(&wallet, &harness.usdc_mint);
+    let treasury = get_treasury_address(harness.usdc_mint);
+    for account in [harness.alice_usdc_account, charity_ata, treasury] {
+        svm.set_account(account, Account::default()).unwrap();
+    }
+
+    let (plan_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    let rent_exempt = svm.minimum_balance_for_rent_exemption(plan_account.data.len());
+    let owner_lamports = svm.get_balance(&harness.alice.pubkey()).unwrap();
+    let keeper_lamports = svm.get_balance(&harness.bob.pubkey()).unwrap();
+
+    // A separate fee payer, so the keeper's balance only reflects the rent it paid
+    let fee_payer = Keypair::new();
+    svm.airdrop(&fee_payer.pubkey(), 1_000_000_000).unwrap();
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS + 1);
+    let instruction = instructions::settle_expired(
+        harness.bob.pubkey(),
+        harness.alice.pubkey(),
+        harness.usdc_mint,
+        meditation_plan,
+        plan.beneficiary,
+        Some(wallet),
+    );
+    send_transaction_from_instructions(
+        &mut svm,
+        vec![instruction],
+        &[&fee_payer, &harness.bob],
+        &fee_payer.pubkey(),
+    )
+    .expect("Settling an expired plan should succeed");
+
+    for account in [
+        practitioner,
+        harness.alice_usdc_account,
+        charity_ata,
+        treasury,
+    ] {
+        assert!(svm.get_account(&account).is_some());
+    }
+    assert_eq!(
+        svm.get_balance(&harness.bob.pubkey()).unwrap(),
+        keeper_lamports,
+        "Keeper should be reimbursed for every account it created"
+    );
+    let balance = get_token_account_balance(&svm, &charity_ata);
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+
+    // The reserve covers exactly these accounts, so nothing is left for the owner
+    let (plan_account, _plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan_account.lamports, rent_exempt);
+    assert_eq!(
+        svm.get_balance(&harness.alice.pubkey()).unwrap(),
+        owner_lamports
+    );
+}
+
+#[test]
+fn test_settle_expired_pays_beneficiary() {
+    let (mut svm, harness) = TestHarness::new();
+    let beneficiary = Keypair::new().pubkey();
+    let (meditation_plan, vault) = create_plan_with_beneficiary(&mut svm, &harness, beneficiary)
+        .expect("Initialize with a beneficiary should succeed");
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS + 1);
+    execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    )
+    .expect("Settling an expired plan should succeed");
+
+    let beneficiary_ata = get_associated_token_address(&beneficiary, &harness.usdc_mint);
+    let balance = get_token_account_balance(&svm, &beneficiary_ata);
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+}
+
+#[test]
+fn test_settle_expired_during_grace_period_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS);
+    let result = execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    );
+    assert!(
+        result.is_err(),
+        "Settling should fail during the grace period"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: GracePeriodActive"),
+        "Incorrect error for grace period"
+    );
+}
+
+#[test]
+fn test_settle_expired_paused_plan_waits_for_pause_allowance() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    execute_pause_plan(&mut svm, &harness.alice, meditation_plan).expect("Pausing should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS + 1);
+    let result = execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    );
+    assert!(
+        result.is_err(),
+        "Settling a paused plan should wait for the pause allowance"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: GracePeriodActive"),
+        "Incorrect error for grace period"
+    );
+
+    set_clock(
+        &mut svm,
+        plan.end_at + SETTLEMENT_GRACE_SECONDS + MAX_PAUSE_SECONDS + 1,
+    );
+    let result = execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Settling a paused plan should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.status, PlanStatus::Completed);
+}
+
+#[test]
+fn test_settle_completed_plan_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+    execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Complete should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS + 1);
+    let result = execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.alice.pubkey(),
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_err(), "Settling a completed plan should fail");
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: PlanCompleted"),
+        "Incorrect error for completed plan"
+    );
+}
+
+#[test]
+fn test_settle_expired_wrong_owner_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.end_at + SETTLEMENT_GRACE_SECONDS + 1);
+    let result = execute_settle_expired(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.bob.pubkey(),
+        meditation_plan,
+        vault,
+    );
+    assert!(
+        result.is_err(),
+        "Settling should fail when paying someone other than the owner"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAccess"),
+        "Incorrect error for unauthorized"
+    );
+}
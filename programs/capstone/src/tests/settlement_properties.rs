@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+
+use anchor_lang::AnchorSerialize;
+use proptest::prelude::*;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::test_helpers::{STARTED_AT, USDC_TOKEN};
+use crate::{MeditationAttestation, MeditationPlan, DAY_IN_SECONDS, MAX_PAUSE_SECONDS};
+
+const USDC_DECIMALS: u8 = 6;
+
+/// Something the owner does to a plan, `at` seconds after it started
+#[derive(Clone, Debug)]
+enum Action {
+    Attest { at: i64, duration: i64 },
+    IncreaseStake { at: i64, amount: u64 },
+    Extend { at: i64, days: u8 },
+    Pause { at: i64, length: i64 },
+}
+
+impl Action {
+    fn at(&self) -> i64 {
+        match self {
+            Action::Attest { at, .. }
+            | Action::IncreaseStake { at, .. }
+            | Action::Extend { at, .. }
+            | Action::Pause { at, .. } => *at,
+        }
+    }
+}
+
+/// Plan parameters within the instruction limits, except the stake which goes well past the
+/// 500 token cap so the invariants hold before it is raised
+#[derive(Clone, Debug)]
+struct PlanCase {
+    number_of_days: u8,
+    daily_frequency: u8,
+    duration_minutes: u8,
+    commitment_stake: u64,
+    penalty_bps: u16,
+}
+
+impl PlanCase {
+    fn plan(&self) -> MeditationPlan {
+        let owner = Keypair::new().pubkey();
+        let mut plan = MeditationPlan::new(
+            owner,
+            owner,
+            1,
+            255,
+            self.number_of_days,
+            self.daily_frequency,
+            self.duration_minutes,
+            self.commitment_stake,
+            STARTED_AT,
+            Pubkey::default(),
+        );
+        plan.penalty_bps = self.penalty_bps;
+        plan
+    }
+}
+
+fn plan_strategy() -> impl Strategy<Value = PlanCase> {
+    (
+        7u8..=30,
+        1u8..=4,
+        5u8..=60,
+        10u64..=100_000,
+        0u16..=MeditationPlan::FULL_PENALTY_BPS,
+    )
+        .prop_map(
+            |(number_of_days, daily_frequency, duration_minutes, stake, penalty_bps)| PlanCase {
+                number_of_days,
+                daily_frequency,
+                duration_minutes,
+                commitment_stake: stake * USDC_TOKEN,
+                penalty_bps,
+            },
+        )
+}
+
+fn action_strategy() -> impl Strategy<Value = Action> {
+    let at = 0..60 * DAY_IN_SECONDS;
+    prop_oneof![
+        8 => (at.clone(), 0..=MeditationAttestation::MAX_DURATION + 60)
+            .prop_map(|(at, duration)| Action::Attest { at, duration }),
+        1 => (at.clone(), 0..=200 * USDC_TOKEN)
+            .prop_map(|(at, amount)| Action::IncreaseStake { at, amount }),
+        1 => (at.clone(), 0u8..=10).prop_map(|(at, days)| Action::Extend { at, days }),
+        1 => (at, 0..=MAX_PAUSE_SECONDS + DAY_IN_SECONDS)
+            .prop_map(|(at, length)| Action::Pause { at, length }),
+    ]
+}
+
+/// Applies the actions in time order and checks the invariants that must hold after every
+/// accepted action. Rejected actions are rolled back, as their transaction would be.
+fn run(
+    plan: &mut MeditationPlan,
+    mut actions: Vec<Action>,
+) -> std::result::Result<(), TestCaseError> {
+    actions.sort_by_key(Action::at);
+    for action in actions {
+        let now = plan.start_at + action.at();
+        let mut next = plan.clone();
+        let accepted = match action {
+            Action::Attest { duration, .. } => next
+                .save_attestation(next.owner, now, now + duration, now + duration)
+                .is_ok(),
+            Action::IncreaseStake { amount, .. } => {
+                next.increase_stake(amount, USDC_DECIMALS, now).is_ok()
+            }
+            Action::Extend { days, .. } => next.extend(days, now).is_ok(),
+            Action::Pause { length, .. } => {
+                next.pause(now).is_ok() && next.resume(now + length).is_ok()
+            }
+        };
+        if accepted {
+            assert_invariants(&next)?;
+            *plan = next;
+        }
+    }
+    Ok(())
+}
+
+fn assert_invariants(plan: &MeditationPlan) -> std::result::Result<(), TestCaseError> {
+    prop_assert!(
+        plan.rewards <= plan.commitment_stake,
+        "Rewards credited so far exceed the stake"
+    );
+    prop_assert!(plan.sessions_completed() <= plan.total_sessions());
+
+    let mut sessions_per_day = HashMap::new();
+    for attestation in &plan.attestations {
+        let day = (attestation.started_at - plan.start_at).div_euclid(DAY_IN_SECONDS);
+        *sessions_per_day.entry(day).or_insert(0u8) += 1;
+    }
+    for (day, sessions) in sessions_per_day {
+        prop_assert!(
+            sessions <= plan.daily_frequency,
+            "Day {day} has {sessions} sessions"
+        );
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn test_settlement_splits_the_stake(
+        case in plan_strategy(),
+        actions in prop::collection::vec(action_strategy(), 0..160),
+    ) {
+        let mut plan = case.plan();
+        run(&mut plan, actions)?;
+
+        let projected = plan.projected_settlement(plan.end_at).unwrap();
+        plan.complete(plan.dispute_window_ends_at() + 1).unwrap();
+
+        prop_assert_eq!(plan.rewards + plan.penalties, plan.commitment_stake);
+        prop_assert_eq!(projected.rewards, plan.rewards);
+        prop_assert_eq!(projected.penalties, plan.penalties);
+        if plan.all_sessions_completed() {
+            prop_assert_eq!(plan.penalties, 0);
+        }
+    }
+
+    #[test]
+    fn test_settlement_empties_vault(
+        case in plan_strategy(),
+        actions in prop::collection::vec(action_strategy(), 0..160),
+        accrued_yield in 0..=1_000 * USDC_TOKEN,
+        owner_yield_bps in 0u64..=10_000,
+        external_deposits in 0..=1_000 * USDC_TOKEN,
+        shortfall_bps in 0u64..=10_000,
+        short in any::<bool>(),
+        has_charity in any::<bool>(),
+    ) {
+        let mut plan = case.plan();
+        run(&mut plan, actions)?;
+        plan.complete(plan.dispute_window_ends_at() + 1).unwrap();
+        plan.accrued_yield = accrued_yield;
+        if has_charity {
+            plan.charity = Pubkey::new_unique();
+        }
+
+        let owner_yield = accrued_yield * owner_yield_bps / 10_000;
+        let principal_and_yield = plan.commitment_stake + accrued_yield;
+        let vault_balance = if short {
+            principal_and_yield - principal_and_yield * shortfall_bps / 10_000
+        } else {
+            principal_and_yield + external_deposits
+        };
+        let (payout, protocol_yield) = plan.settle_vault(vault_balance, owner_yield).unwrap();
+
+        prop_assert!(payout <= vault_balance);
+        let donation = if plan.has_charity() { plan.penalties } else { 0 };
+        let treasury_share = plan.treasury_share(protocol_yield).unwrap();
+        prop_assert_eq!(
+            vault_balance - payout - donation - treasury_share,
+            0,
+            "The vault is empty once the owner, charity and treasury are paid"
+        );
+        prop_assert_eq!(
+            plan.rewards + plan.penalties + plan.accrued_yield + plan.shortfall,
+            principal_and_yield,
+            "The shortfall only ever replaces principal and yield"
+        );
+    }
+
+    #[test]
+    fn test_settlement_is_idempotent(
+        case in plan_strategy(),
+        actions in prop::collection::vec(action_strategy(), 0..160),
+        later in 0..30 * DAY_IN_SECONDS,
+    ) {
+        let mut plan = case.plan();
+        run(&mut plan, actions)?;
+        let now = plan.dispute_window_ends_at() + 1;
+        plan.complete(now).unwrap();
+        let settled = plan.clone().try_to_vec().unwrap();
+        let projected = plan.projected_settlement(now + later).unwrap();
+
+        prop_assert!(plan.complete(now + later).is_err());
+        prop_assert_eq!(&plan.clone().try_to_vec().unwrap(), &settled, "Completing again changed the plan");
+        prop_assert_eq!(plan.projected_settlement(now + later).unwrap(), projected);
+        prop_assert_eq!(projected.rewards, plan.rewards);
+        prop_assert_eq!(projected.penalties, plan.penalties);
+    }
+}
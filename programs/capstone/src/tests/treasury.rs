@@ -0,0 +1,207 @@
+use litesvm::LiteSVM;
+use solana_kite::get_token_account_balance;
+use solana_pubkey::Pubkey;
+
+use crate::test_helpers::{
+    attest_all_sessions, create_standard_plan, execute_complete, execute_complete_with_lending,
+    execute_initialize_with_lending, execute_withdraw_treasury, expected_mock_lending_yield,
+    generate_id, get_config, get_meditation_plan, get_treasury_address, set_clock, set_config,
+    setup_mock_lending, TestHarness, COMMITMENT_STAKE, HUNDY_USDC,
+};
+use crate::Config;
+
+const PENALTY_FEE_BPS: u16 = 1_000;
+const EXPECTED_PENALTY_FEE: u64 = COMMITMENT_STAKE * PENALTY_FEE_BPS as u64 / 10_000;
+
+/// Completes a standard plan without any sessions under the penalty fee, returning the plan and vault
+fn complete_with_penalty_fee(svm: &mut LiteSVM, harness: &TestHarness) -> (Pubkey, Pubkey) {
+    let config = get_config(svm);
+    set_config(
+        svm,
+        Config {
+            penalty_fee_bps: PENALTY_FEE_BPS,
+            ..config
+        },
+    );
+
+    let (meditation_plan, vault) = create_standard_plan(svm, harness);
+    let (_account, plan) = get_meditation_plan(svm, &meditation_plan);
+    set_clock(svm, plan.dispute_window_ends_at() + 1);
+    execute_complete(
+        svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    )
+    .expect("Complete should succeed");
+
+    (meditation_plan, vault)
+}
+
+#[test]
+fn test_complete_sends_penalty_fee_to_treasury() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = complete_with_penalty_fee(&mut svm, &harness);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.penalties, COMMITMENT_STAKE);
+    assert_eq!(plan.protocol_fee, EXPECTED_PENALTY_FEE);
+
+    // The treasury collects the penalties in full, of which the fee is recorded on the plan
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+
+    // The fee only comes out of penalties - the owner's payout is unchanged
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC - COMMITMENT_STAKE);
+}
+
+#[test]
+fn test_complete_without_fees_still_collects_penalties() {
+    let (mut svm, harness) = TestHarness::new();
+    let (meditation_plan, vault) = create_standard_plan(&mut svm, &harness);
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    set_clock(&mut svm, plan.dispute_window_ends_at() + 1);
+
+    let result = execute_complete(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.alice,
+        harness.alice_usdc_account,
+        meditation_plan,
+        vault,
+    );
+    assert!(result.is_ok(), "Complete should succeed");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.protocol_fee, 0);
+
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), COMMITMENT_STAKE);
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+}
+
+#[test]
+fn test_complete_with_lending_sends_yield_fee_to_treasury() {
+    let (mut svm, harness) = TestHarness::new();
+    let owner_yield_bps = 8_000;
+    let yield_fee_bps = 5_000;
+    let lending = setup_mock_lending(&mut svm, &harness, owner_yield_bps);
+    let config = get_config(&mut svm);
+    set_config(
+        &mut svm,
+        Config {
+            yield_fee_bps,
+            ..config
+        },
+    );
+
+    let (meditation_plan, vault) =
+        execute_initialize_with_lending(&mut svm, &harness, &lending, generate_id())
+            .expect("Initialize with lending should succeed");
+    attest_all_sessions(&mut svm, &harness.alice, meditation_plan);
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    let completed_at = plan.end_at + 1;
+    set_clock(&mut svm, completed_at);
+
+    let result =
+        execute_complete_with_lending(&mut svm, &harness, &lending, meditation_plan, vault);
+    assert!(result.is_ok(), "Complete with lending should succeed");
+
+    let accrued_yield = expected_mock_lending_yield(COMMITMENT_STAKE, completed_at - plan.start_at);
+    let owner_yield = accrued_yield * owner_yield_bps as u64 / 10_000;
+    let expected_fee = (accrued_yield - owner_yield) * yield_fee_bps as u64 / 10_000;
+    assert!(expected_fee > 0, "The protocol yield should be charged");
+
+    let (_account, plan) = get_meditation_plan(&mut svm, &meditation_plan);
+    assert_eq!(plan.protocol_fee, expected_fee);
+
+    // The treasury collects all of the protocol yield, of which the fee is recorded on the plan
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), accrued_yield - owner_yield);
+
+    let balance = get_token_account_balance(&svm, &vault);
+    assert_eq!(balance.unwrap(), 0);
+
+    let balance = get_token_account_balance(&svm, &harness.alice_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC + owner_yield);
+}
+
+#[test]
+fn test_withdraw_treasury_succeeds() {
+    let (mut svm, harness) = TestHarness::new();
+    complete_with_penalty_fee(&mut svm, &harness);
+
+    let result = execute_withdraw_treasury(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        harness.bob_usdc_account,
+        COMMITMENT_STAKE,
+    );
+    assert!(result.is_ok(), "Withdraw treasury should succeed");
+
+    let balance = get_token_account_balance(&svm, &get_treasury_address(harness.usdc_mint));
+    assert_eq!(balance.unwrap(), 0);
+
+    let balance = get_token_account_balance(&svm, &harness.bob_usdc_account);
+    assert_eq!(balance.unwrap(), HUNDY_USDC + COMMITMENT_STAKE);
+}
+
+#[test]
+fn test_withdraw_treasury_above_balance_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    complete_with_penalty_fee(&mut svm, &harness);
+
+    let result = execute_withdraw_treasury(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.admin,
+        harness.bob_usdc_account,
+        COMMITMENT_STAKE + 1,
+    );
+    assert!(
+        result.is_err(),
+        "Withdrawing more than the treasury holds should fail"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: InvalidWithdrawalAmount"),
+        "Incorrect error for invalid withdrawal amount"
+    );
+}
+
+#[test]
+fn test_unauthorized_withdraw_treasury_fails() {
+    let (mut svm, harness) = TestHarness::new();
+    complete_with_penalty_fee(&mut svm, &harness);
+
+    let result = execute_withdraw_treasury(
+        &mut svm,
+        harness.usdc_mint,
+        &harness.bob,
+        harness.bob_usdc_account,
+        EXPECTED_PENALTY_FEE,
+    );
+    assert!(
+        result.is_err(),
+        "Withdraw treasury should fail for non-admin"
+    );
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Error Code: UnauthorizedAdmin"),
+        "Incorrect error for unauthorized admin"
+    );
+}
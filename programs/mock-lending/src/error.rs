@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum MockLendingError {
+    #[msg("Deposit amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Pool reserve cannot cover the withdrawal")]
+    InsufficientLiquidity,
+    #[msg("Position has nothing to withdraw")]
+    EmptyPosition,
+}
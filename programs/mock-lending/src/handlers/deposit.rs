@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::MockLendingError;
+use crate::state::{Pool, Position};
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Owner of the deposited tokens - usually a PDA signing through CPI
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = mint,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = Position::DISCRIMINATOR.len() + Position::INIT_SPACE,
+        seeds = [b"position", pool.key().as_ref(), depositor.key().as_ref()],
+        bump
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = depositor,
+        token::token_program = token_program,
+    )]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pub reserve: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Deposit<'info> {
+    pub fn deposit(&mut self, amount: u64, bumps: &DepositBumps) -> Result<()> {
+        require_gt!(amount, 0, MockLendingError::InvalidAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        if self.position.depositor == Pubkey::default() {
+            self.position.set_inner(Position {
+                bump: bumps.position,
+                deposited_at: now,
+                depositor: self.depositor.key(),
+                pool: self.pool.key(),
+                principal: 0,
+            });
+        }
+        self.position.accrue(self.pool.rate_bps, now)?;
+        self.position.principal = self
+            .position
+            .principal
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.pool.total_deposits = self
+            .pool
+            .total_deposits
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let cpi_accounts = TransferChecked {
+            from: self.depositor_token_account.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.reserve.to_account_info(),
+            authority: self.depositor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+}
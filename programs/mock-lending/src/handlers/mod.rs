@@ -0,0 +1,7 @@
+pub mod deposit;
+pub mod initialize_pool;
+pub mod withdraw;
+
+pub use deposit::*;
+pub use initialize_pool::*;
+pub use withdraw::*;
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::error::MockLendingError;
+use crate::state::{Pool, Position};
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub depositor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pool", mint.key().as_ref()],
+        bump = pool.bump,
+        has_one = mint,
+    )]
+    pub pool: Account<'info, Pool>,
+
+    #[account(
+        mut,
+        seeds = [b"position", pool.key().as_ref(), depositor.key().as_ref()],
+        bump = position.bump,
+        has_one = depositor,
+        has_one = pool,
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = depositor,
+        token::token_program = token_program,
+    )]
+    pub depositor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program,
+    )]
+    pub reserve: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Withdraw<'info> {
+    /// Withdraws the full position, principal plus accrued interest
+    pub fn withdraw(&mut self) -> Result<()> {
+        require_gt!(self.position.principal, 0, MockLendingError::EmptyPosition);
+
+        let now = Clock::get()?.unix_timestamp;
+        let deposited = self.position.principal;
+        self.position.accrue(self.pool.rate_bps, now)?;
+        let amount = self.position.principal;
+        require_gte!(
+            self.reserve.amount,
+            amount,
+            MockLendingError::InsufficientLiquidity
+        );
+
+        self.position.principal = 0;
+        self.pool.total_deposits = self.pool.total_deposits.saturating_sub(deposited);
+
+        let mint_key = self.mint.key();
+        let seeds = &[b"pool", mint_key.as_ref(), &[self.pool.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: self.reserve.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.depositor_token_account.to_account_info(),
+            authority: self.pool.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer);
+
+        transfer_checked(cpi_ctx, amount, self.mint.decimals)
+    }
+}
@@ -0,0 +1,38 @@
+#![allow(unexpected_cfgs)]
+#![allow(deprecated)]
+
+//! A deliberately tiny lending market for local development and tests.
+//!
+//! Each mint has a single `Pool` paying simple interest at a fixed annual rate. Deposits are tracked
+//! per depositor in a `Position`, and interest is paid out of the pool reserve on withdrawal, so the
+//! reserve must be funded with enough liquidity to cover it.
+
+pub mod error;
+pub mod handlers;
+pub mod state;
+
+use anchor_lang::prelude::*;
+
+pub use handlers::*;
+pub use state::*;
+
+declare_id!("HDM3eY18WMgEj3aeghbTXDj3sjsmCexPXfAfx8U2LXw4");
+
+pub const YEAR_IN_SECONDS: i64 = 365 * 24 * 60 * 60;
+
+#[program]
+pub mod mock_lending {
+    use super::*;
+
+    pub fn initialize_pool(ctx: Context<InitializePool>, rate_bps: u16) -> Result<()> {
+        ctx.accounts.initialize_pool(rate_bps, &ctx.bumps)
+    }
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.deposit(amount, &ctx.bumps)
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        ctx.accounts.withdraw()
+    }
+}
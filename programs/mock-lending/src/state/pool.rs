@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Pool {
+    pub authority: Pubkey,
+    pub bump: u8,
+    pub mint: Pubkey,
+    /// Simple annual interest rate in basis points
+    pub rate_bps: u16,
+    pub total_deposits: u64,
+}
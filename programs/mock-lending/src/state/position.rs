@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::YEAR_IN_SECONDS;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub bump: u8,
+    /// Last time interest was accrued into the principal
+    pub deposited_at: i64,
+    pub depositor: Pubkey,
+    pub pool: Pubkey,
+    pub principal: u64,
+}
+
+impl Position {
+    /// Rolls simple interest earned since `deposited_at` into the principal
+    pub fn accrue(&mut self, rate_bps: u16, now: i64) -> Result<()> {
+        let elapsed = now.saturating_sub(self.deposited_at).max(0) as u128;
+        let interest = (self.principal as u128)
+            .checked_mul(rate_bps as u128)
+            .and_then(|amount| amount.checked_mul(elapsed))
+            .and_then(|amount| amount.checked_div(YEAR_IN_SECONDS as u128 * 10_000))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        self.principal = u64::try_from(interest)
+            .ok()
+            .and_then(|interest| self.principal.checked_add(interest))
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        self.deposited_at = now;
+
+        Ok(())
+    }
+}